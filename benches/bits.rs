@@ -0,0 +1,149 @@
+//! Compare reading the packets of 2021 day 16 bit by bit from an iterator of `bool`s,
+//! as it was first done, with reading them from the bytes with a `BitReader`.
+//!
+//! Run with `cargo bench --bench bits`.
+
+use std::iter::Peekable;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+#[path = "../src/utils/bits/reader.rs"]
+mod reader;
+
+use reader::{hex_to_bytes, BitReader};
+
+/// A generated transmission of 100 nested packets.
+const DATA: &str = include_str!("data/day16.txt");
+
+/// Bits that the packets can be read from.
+trait BitSource {
+    /// The bits of a sub-source, consumed from this one.
+    type Sub<'s>: BitSource
+    where
+        Self: 's;
+
+    fn read_bit(&mut self) -> Option<bool>;
+
+    /// Read `n` bits into an integer, the first bit read being the most significant.
+    fn read_bits(&mut self, n: u32) -> Option<u64>;
+
+    fn is_empty(&mut self) -> bool;
+
+    /// Take the next `n` bits as their own source.
+    fn sub(&mut self, n: usize) -> Option<Self::Sub<'_>>;
+}
+
+/// Bits read one at a time from hexadecimal characters, as day 16 used to.
+struct BoolIter<'a>(Peekable<Box<dyn Iterator<Item = bool> + 'a>>);
+
+impl<'a> BoolIter<'a> {
+    fn new(iter: impl Iterator<Item = bool> + 'a) -> Self {
+        Self((Box::new(iter) as Box<dyn Iterator<Item = bool>>).peekable())
+    }
+
+    fn from_hex(chars: impl Iterator<Item = char> + 'a) -> Self {
+        Self::new(
+            chars
+                .map(|c| c.to_digit(16).unwrap() as u8)
+                .flat_map(|n| (0..4).rev().map(move |i| ((n >> i) & 1) != 0)),
+        )
+    }
+}
+
+impl BitSource for BoolIter<'_> {
+    type Sub<'s>
+        = BoolIter<'s>
+    where
+        Self: 's;
+
+    fn read_bit(&mut self) -> Option<bool> {
+        self.0.next()
+    }
+
+    /// Collect the bits one by one, as `utils::collect_n_bits` used to.
+    fn read_bits(&mut self, n: u32) -> Option<u64> {
+        let mut r = 0;
+        for _ in 0..n {
+            r = (r << 1) | self.0.next()? as u64;
+        }
+        Some(r)
+    }
+
+    fn is_empty(&mut self) -> bool {
+        self.0.peek().is_none()
+    }
+
+    fn sub(&mut self, n: usize) -> Option<Self::Sub<'_>> {
+        Some(BoolIter::new((&mut self.0).take(n)))
+    }
+}
+
+impl<'a> BitSource for BitReader<'a> {
+    type Sub<'s>
+        = BitReader<'a>
+    where
+        Self: 's;
+
+    fn read_bit(&mut self) -> Option<bool> {
+        BitReader::read_bit(self).ok()
+    }
+
+    fn read_bits(&mut self, n: u32) -> Option<u64> {
+        BitReader::read_bits(self, n).ok()
+    }
+
+    fn is_empty(&mut self) -> bool {
+        BitReader::is_empty(self)
+    }
+
+    fn sub(&mut self, n: usize) -> Option<Self::Sub<'_>> {
+        self.sub_reader(n).ok()
+    }
+}
+
+/// The sum of the versions of a packet and its subpackets.
+fn version_sum(bits: &mut impl BitSource) -> Option<u64> {
+    let mut sum = bits.read_bits(3)?;
+    let type_id = bits.read_bits(3)?;
+
+    if type_id == 4 {
+        loop {
+            let last = !bits.read_bit()?;
+            bits.read_bits(4)?;
+            if last {
+                break;
+            }
+        }
+    } else if bits.read_bit()? {
+        let n_subpackets = bits.read_bits(11)?;
+        for _ in 0..n_subpackets {
+            sum += version_sum(bits)?;
+        }
+    } else {
+        let len_subpackets = bits.read_bits(15)?;
+        let mut sub_bits = bits.sub(len_subpackets as usize)?;
+        while !sub_bits.is_empty() {
+            sum += version_sum(&mut sub_bits)?;
+        }
+    }
+
+    Some(sum)
+}
+
+fn bench_day16(c: &mut Criterion) {
+    let data = DATA.trim();
+    let from_iter = || version_sum(&mut BoolIter::from_hex(black_box(data).chars()));
+    let from_reader = || {
+        let bytes = hex_to_bytes(black_box(data)).unwrap();
+        version_sum(&mut BitReader::new(&bytes))
+    };
+    assert_eq!(from_iter(), from_reader());
+
+    let mut group = c.benchmark_group("day16 version sum");
+    group.bench_function("bool iterator", |b| b.iter(from_iter));
+    group.bench_function("bit reader", |b| b.iter(from_reader));
+    group.finish();
+}
+
+criterion_group!(benches, bench_day16);
+criterion_main!(benches);
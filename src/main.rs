@@ -112,6 +112,13 @@ fn main() -> anyhow::Result<()> {
             println!("{} {}", "R =".fg::<Cyan>(), "TODO".fg::<Red>(),);
         };
 
+        let print_memo_stats = || {
+            // The stats of the memoized functions used by the question, if any
+            if let Some(stats) = utils::memo::take_stats() {
+                println!("{} {}", "  memo:".fg::<Blue>(), stats.fg::<Cyan>());
+            }
+        };
+
         let (r, dur) = timer(|| solution.q1(&data));
         if r.is_empty() {
             print_todo();
@@ -119,6 +126,7 @@ fn main() -> anyhow::Result<()> {
             print_result(&r, dur);
             total_duration += dur;
         }
+        print_memo_stats();
 
         let (r, dur) = timer(|| solution.q2(&data));
         if r.is_empty() {
@@ -127,6 +135,7 @@ fn main() -> anyhow::Result<()> {
             print_result(&r, dur);
             total_duration += dur;
         }
+        print_memo_stats();
     }
 
     println!(
@@ -0,0 +1,147 @@
+//! Reading bit streams packed in bytes, like the transmissions of 2021 day 16.
+//!
+//! The reader is in its own file so that the benchmarks can include it on its own.
+
+mod reader;
+
+pub use reader::{hex_to_bytes, BitError, BitReader};
+
+#[cfg(test)]
+mod test {
+    use crate::utils::test_rng::{Rng, SEED};
+
+    use super::{hex_to_bytes, reader::Bits, BitError, BitReader};
+
+    /// Write bits into a byte buffer, in the order a [`BitReader`] reads them.
+    #[derive(Default)]
+    struct BitWriter {
+        bytes: Vec<u8>,
+        /// Number of bits written
+        len: usize,
+    }
+
+    impl BitWriter {
+        fn write_bit(&mut self, bit: bool) {
+            self.write_bits(bit as u8, 1)
+        }
+
+        /// Write the `n` lowest bits of an integer, the most significant first.
+        fn write_bits<T: Bits + Into<u128>>(&mut self, value: T, n: u32) {
+            assert!(n <= T::BITS, "cannot write {n} bits from {} bits", T::BITS);
+
+            let value = value.into();
+            let mut n = n as usize;
+            while n > 0 {
+                let skip = self.len % 8;
+                if skip == 0 {
+                    self.bytes.push(0);
+                }
+
+                let take = (8 - skip).min(n);
+                let chunk = ((value >> (n - take)) as u8) & (((1u16 << take) - 1) as u8);
+                *self.bytes.last_mut().unwrap() |= chunk << (8 - skip - take);
+
+                self.len += take;
+                n -= take;
+            }
+        }
+
+        /// A reader over exactly the written bits.
+        fn reader(&self) -> BitReader<'_> {
+            BitReader::new(&self.bytes).sub_reader(self.len).unwrap()
+        }
+    }
+
+    #[test]
+    fn read() {
+        let bytes = hex_to_bytes("D2FE28").unwrap();
+        assert_eq!(vec![0xD2, 0xFE, 0x28], bytes);
+
+        let mut reader = BitReader::new(&bytes);
+        assert_eq!(Ok(6u8), reader.read_bits(3));
+        assert_eq!(Ok(4u8), reader.read_bits(3));
+        assert_eq!(Ok(true), reader.read_bit());
+        assert_eq!(Ok(0b01_1111_1100_0101u16), reader.read_bits(14));
+        assert_eq!(3, reader.remaining());
+
+        // A failed read reports its offset and consumes nothing
+        assert_eq!(
+            Err(BitError::UnexpectedEnd {
+                offset: 21,
+                wanted: 4,
+                remaining: 3
+            }),
+            reader.read_bits::<u8>(4)
+        );
+        assert_eq!(Ok(0u8), reader.read_bits(3));
+        assert!(reader.is_empty());
+
+        assert_eq!(
+            Err(BitError::InvalidHex { index: 2, c: 'G' }),
+            hex_to_bytes("00G0")
+        );
+        assert_eq!(vec![0xAB, 0xC0], hex_to_bytes("ABC").unwrap());
+    }
+
+    #[test]
+    fn sub_reader() {
+        let bytes = [0b1010_1100, 0b0101_0011];
+        let mut reader = BitReader::new(&bytes);
+        reader.read_bits::<u8>(2).unwrap();
+
+        let mut sub = reader.sub_reader(10).unwrap();
+        assert_eq!(4, reader.remaining());
+        assert_eq!(Ok(0b0011u8), reader.read_bits(4));
+
+        // The sub-reader keeps absolute offsets and stops at its own end
+        assert_eq!(10, sub.remaining());
+        assert_eq!(Ok(0b10_1100_0101u16), sub.read_bits(10));
+        assert_eq!(
+            Err(BitError::UnexpectedEnd {
+                offset: 12,
+                wanted: 1,
+                remaining: 0
+            }),
+            sub.read_bit()
+        );
+        assert!(reader.sub_reader(1).is_err());
+    }
+
+    #[test]
+    fn write_read_roundtrip() {
+        let mut rng = Rng::new(SEED);
+        for _ in 0..200 {
+            let mut writer = BitWriter::default();
+            let mut written = vec![];
+            for _ in 0..rng.range(0..20) {
+                let n = rng.range(0..65) as u32;
+                let value = rng.next_u64() & u64::MAX.checked_shr(64 - n).unwrap_or(0);
+                writer.write_bits(value, n);
+                written.push((value, n));
+            }
+
+            let total: u32 = written.iter().map(|(_, n)| n).sum();
+            assert_eq!(total as usize, writer.len);
+            assert_eq!(writer.len.div_ceil(8), writer.bytes.len());
+
+            let mut reader = writer.reader();
+            for (value, n) in written {
+                assert_eq!(Ok(value), reader.read_bits(n));
+            }
+            assert!(reader.is_empty());
+        }
+    }
+
+    #[test]
+    fn wide_values() {
+        let mut writer = BitWriter::default();
+        writer.write_bit(true);
+        writer.write_bits(u128::MAX - 1, 128);
+        writer.write_bits(0x5u8, 3);
+
+        let mut reader = writer.reader();
+        assert_eq!(Ok(true), reader.read_bit());
+        assert_eq!(Ok(u128::MAX - 1), reader.read_bits(128));
+        assert_eq!(Ok(0x5u8), reader.read_bits(3));
+    }
+}
@@ -0,0 +1,156 @@
+use std::fmt::Display;
+
+/// An unsigned integer that bits can be read into.
+pub trait Bits: Copy {
+    const BITS: u32;
+
+    /// Convert from a `u128`, truncating the higher bits.
+    fn from_u128(v: u128) -> Self;
+}
+
+macro_rules! impl_bits {
+    ($($t:ty),*) => {
+        $(impl Bits for $t {
+            const BITS: u32 = <$t>::BITS;
+
+            fn from_u128(v: u128) -> Self {
+                v as _
+            }
+        })*
+    };
+}
+
+impl_bits!(u8, u16, u32, u64, u128, usize);
+
+/// An error while reading bits. Offsets are in bits, from the start of the buffer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BitError {
+    /// There are not enough bits left to read
+    UnexpectedEnd {
+        offset: usize,
+        wanted: usize,
+        remaining: usize,
+    },
+    /// A character is not an hexadecimal digit
+    InvalidHex { index: usize, c: char },
+}
+
+impl Display for BitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnexpectedEnd {
+                offset,
+                wanted,
+                remaining,
+            } => write!(
+                f,
+                "tried to read {wanted} bits at offset {offset}, but only {remaining} remain"
+            ),
+            Self::InvalidHex { index, c } => {
+                write!(f, "invalid hexadecimal digit {c:?} at index {index}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BitError {}
+
+/// Decode hexadecimal digits into bytes, the first digit being the most significant.
+/// An odd number of digits is padded with a final `0`.
+pub fn hex_to_bytes(s: &str) -> Result<Vec<u8>, BitError> {
+    let mut bytes = Vec::with_capacity(s.len().div_ceil(2));
+    for (index, c) in s.chars().enumerate() {
+        let digit = c.to_digit(16).ok_or(BitError::InvalidHex { index, c })? as u8;
+        if index % 2 == 0 {
+            bytes.push(digit << 4);
+        } else {
+            *bytes.last_mut().unwrap() |= digit;
+        }
+    }
+    Ok(bytes)
+}
+
+/// Read bits from a byte buffer, starting from the most significant bit of the first byte.
+#[derive(Debug, Clone)]
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    /// Offset of the next bit to read
+    pos: usize,
+    /// Offset after the last bit that can be read
+    end: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            pos: 0,
+            end: bytes.len() * 8,
+        }
+    }
+
+    /// The number of bits left to read.
+    pub fn remaining(&self) -> usize {
+        self.end - self.pos
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Check that there are at least `n` bits left.
+    fn check(&self, n: usize) -> Result<(), BitError> {
+        if n <= self.remaining() {
+            Ok(())
+        } else {
+            Err(BitError::UnexpectedEnd {
+                offset: self.pos,
+                wanted: n,
+                remaining: self.remaining(),
+            })
+        }
+    }
+
+    pub fn read_bit(&mut self) -> Result<bool, BitError> {
+        self.read_bits::<u8>(1).map(|b| b == 1)
+    }
+
+    /// Read `n` bits into an integer, the first bit read being the most significant.
+    /// Nothing is consumed on error.
+    ///
+    /// # Panics
+    /// If `n` is greater than the number of bits of `T`.
+    pub fn read_bits<T: Bits>(&mut self, n: u32) -> Result<T, BitError> {
+        assert!(n <= T::BITS, "cannot read {n} bits into {} bits", T::BITS);
+        self.check(n as usize)?;
+
+        // Read whole chunks of bytes at a time
+        let mut value = 0u128;
+        let mut n = n as usize;
+        while n > 0 {
+            let byte = self.bytes[self.pos / 8];
+            let skip = self.pos % 8;
+            let take = (8 - skip).min(n);
+            let chunk = (byte >> (8 - skip - take)) & (((1u16 << take) - 1) as u8);
+
+            value = (value << take) | chunk as u128;
+            self.pos += take;
+            n -= take;
+        }
+
+        Ok(T::from_u128(value))
+    }
+
+    /// Split the next `n` bits into their own reader, and skip them in this one.
+    pub fn sub_reader(&mut self, n: usize) -> Result<BitReader<'a>, BitError> {
+        self.check(n)?;
+
+        let sub = Self {
+            bytes: self.bytes,
+            pos: self.pos,
+            end: self.pos + n,
+        };
+        self.pos += n;
+        Ok(sub)
+    }
+}
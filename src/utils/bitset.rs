@@ -0,0 +1,270 @@
+use std::{
+    fmt::Debug,
+    ops::{BitAnd, BitOr, Sub},
+};
+
+/// Operations on sets stored as slices of 64-bit words, shared by the set types.
+mod words {
+    pub fn contains(words: &[u64], i: usize) -> bool {
+        words.get(i / 64).is_some_and(|w| w >> (i % 64) & 1 == 1)
+    }
+
+    /// Set the bit, and return whether it changed.
+    pub fn set(words: &mut [u64], i: usize, value: bool) -> bool {
+        let (w, bit) = (&mut words[i / 64], 1 << (i % 64));
+        let old = *w & bit != 0;
+        if value {
+            *w |= bit;
+        } else {
+            *w &= !bit;
+        }
+        old != value
+    }
+
+    #[cfg(test)]
+    pub fn len(words: &[u64]) -> usize {
+        words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    #[cfg(test)]
+    pub fn is_subset(a: &[u64], b: &[u64]) -> bool {
+        (0..a.len()).all(|i| a[i] & !b.get(i).copied().unwrap_or(0) == 0)
+    }
+
+    /// Iterate over the set bits, in ascending order.
+    pub fn iter(words: &[u64]) -> impl Iterator<Item = usize> + '_ {
+        words.iter().enumerate().flat_map(|(i, &w)| {
+            let mut w = w;
+            std::iter::from_fn(move || {
+                (w != 0).then(|| {
+                    let bit = w.trailing_zeros() as usize;
+                    w &= w - 1;
+                    i * 64 + bit
+                })
+            })
+        })
+    }
+}
+
+/// A set of small integers in `0..64 * W`, stored as bits in `W` words.
+///
+/// Being `Copy` and `Hash`, it can be used as a cheap memoization key.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BitSet<const W: usize = 1> {
+    words: [u64; W],
+}
+
+impl<const W: usize> BitSet<W> {
+    pub const EMPTY: Self = Self { words: [0; W] };
+
+    #[cfg(test)]
+    pub const fn new() -> Self {
+        Self::EMPTY
+    }
+
+    /// The set of all integers in `0..n`.
+    #[cfg(test)]
+    pub fn full(n: usize) -> Self {
+        assert!(n <= Self::capacity(), "{n} bits do not fit in the set");
+        let mut set = Self::EMPTY;
+        for (i, w) in set.words.iter_mut().enumerate() {
+            let bits = n.saturating_sub(i * 64).min(64);
+            *w = u64::MAX.checked_shr(64 - bits as u32).unwrap_or(0);
+        }
+        set
+    }
+
+    /// The number of integers the set can hold.
+    #[cfg(test)]
+    pub const fn capacity() -> usize {
+        64 * W
+    }
+
+    /// Insert an integer, and return whether it was not already in the set.
+    ///
+    /// # Panics
+    /// If the integer is not lower than the capacity.
+    pub fn insert(&mut self, i: usize) -> bool {
+        words::set(&mut self.words, i, true)
+    }
+
+    /// Remove an integer, and return whether it was in the set.
+    #[cfg(test)]
+    pub fn remove(&mut self, i: usize) -> bool {
+        i < Self::capacity() && words::set(&mut self.words, i, false)
+    }
+
+    /// A copy of the set with the integer inserted.
+    #[must_use]
+    pub fn with(mut self, i: usize) -> Self {
+        self.insert(i);
+        self
+    }
+
+    pub fn contains(&self, i: usize) -> bool {
+        words::contains(&self.words, i)
+    }
+
+    /// The number of integers in the set.
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        words::len(&self.words)
+    }
+
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    #[cfg(test)]
+    pub fn is_subset(&self, other: &Self) -> bool {
+        words::is_subset(&self.words, &other.words)
+    }
+
+    /// Iterate over the integers, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        words::iter(&self.words)
+    }
+
+    /// Iterate over all the subsets of this set, from the empty set to the set itself.
+    ///
+    /// # Panics
+    /// If the set has 64 integers or more, as there would be too many subsets.
+    #[cfg(test)]
+    pub fn subsets(&self) -> impl Iterator<Item = Self> {
+        let elements = self.iter().collect::<Vec<_>>();
+        assert!(elements.len() < 64, "too many subsets to enumerate");
+
+        (0..1u64 << elements.len())
+            .map(move |mask| words::iter(&[mask]).map(|i| elements[i]).collect::<Self>())
+    }
+
+    fn zip_with(self, other: Self, f: impl Fn(u64, u64) -> u64) -> Self {
+        let mut words = self.words;
+        for (w, o) in words.iter_mut().zip(other.words) {
+            *w = f(*w, o);
+        }
+        Self { words }
+    }
+}
+
+impl<const W: usize> Default for BitSet<W> {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+impl<const W: usize> BitOr for BitSet<W> {
+    type Output = Self;
+
+    /// The union of the sets.
+    fn bitor(self, rhs: Self) -> Self {
+        self.zip_with(rhs, |a, b| a | b)
+    }
+}
+
+impl<const W: usize> BitAnd for BitSet<W> {
+    type Output = Self;
+
+    /// The intersection of the sets.
+    fn bitand(self, rhs: Self) -> Self {
+        self.zip_with(rhs, |a, b| a & b)
+    }
+}
+
+impl<const W: usize> Sub for BitSet<W> {
+    type Output = Self;
+
+    /// The difference of the sets.
+    fn sub(self, rhs: Self) -> Self {
+        self.zip_with(rhs, |a, b| a & !b)
+    }
+}
+
+impl<const W: usize> FromIterator<usize> for BitSet<W> {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::EMPTY;
+        set.extend(iter);
+        set
+    }
+}
+
+impl<const W: usize> Extend<usize> for BitSet<W> {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|i| {
+            self.insert(i);
+        });
+    }
+}
+
+impl<const W: usize> Debug for BitSet<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::{BTreeSet, HashSet};
+
+    use crate::utils::test_rng::{Rng, SEED};
+
+    use super::BitSet;
+
+    #[test]
+    fn fixed() {
+        let mut set = BitSet::<2>::new();
+        assert!(set.insert(3));
+        assert!(set.insert(100));
+        assert!(!set.insert(3));
+        assert_eq!(vec![3, 100], set.iter().collect::<Vec<_>>());
+        assert_eq!(2, set.len());
+        assert!(set.remove(3));
+        assert!(!set.remove(3));
+        assert!(!set.remove(1000));
+        assert_eq!("{100}", format!("{set:?}"));
+
+        assert_eq!(70, BitSet::<2>::full(70).len());
+        assert_eq!(128, BitSet::<2>::full(128).len());
+        assert_eq!(Some(69), BitSet::<2>::full(70).iter().last());
+        assert!(BitSet::<1>::full(0).is_empty());
+    }
+
+    #[test]
+    fn subsets() {
+        let set: BitSet = [1, 4, 9].into_iter().collect();
+        let subsets = set.subsets().collect::<Vec<_>>();
+
+        assert_eq!(8, subsets.len());
+        assert_eq!(8, subsets.iter().collect::<HashSet<_>>().len());
+        assert_eq!(BitSet::EMPTY, subsets[0]);
+        assert_eq!(set, subsets[7]);
+        assert!(subsets.iter().all(|s| s.is_subset(&set)));
+    }
+
+    #[test]
+    fn against_model() {
+        let mut rng = Rng::new(SEED);
+        for _ in 0..200 {
+            let mut random_set = || {
+                (0..rng.range(0..30))
+                    .map(|_| rng.range(0..192) as usize)
+                    .collect::<BTreeSet<_>>()
+            };
+            let (a, b) = (random_set(), random_set());
+
+            let (fa, fb): (BitSet<3>, BitSet<3>) =
+                (a.iter().copied().collect(), b.iter().copied().collect());
+
+            let union = a.union(&b).copied().collect::<Vec<_>>();
+            let inter = a.intersection(&b).copied().collect::<Vec<_>>();
+            let diff = a.difference(&b).copied().collect::<Vec<_>>();
+
+            assert_eq!(a.len(), fa.len());
+            assert_eq!(union, (fa | fb).iter().collect::<Vec<_>>());
+            assert_eq!(inter, (fa & fb).iter().collect::<Vec<_>>());
+            assert_eq!(diff, (fa - fb).iter().collect::<Vec<_>>());
+            assert_eq!(a.is_subset(&b), fa.is_subset(&fb));
+        }
+    }
+}
@@ -0,0 +1,260 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    hash::Hash,
+    ops::Range,
+};
+
+use super::intervals::{self, Int};
+
+/// An axis-aligned box in `D` dimensions, made of a half-open range on each axis.
+///
+/// For example, a 2D rectangle or a 3D cuboid.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Aabb<T, const D: usize> {
+    pub axes: [Range<T>; D],
+}
+
+impl<T: Int, const D: usize> Aabb<T, D> {
+    pub fn new(axes: [Range<T>; D]) -> Self {
+        Self { axes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.axes.iter().any(|r| r.start >= r.end)
+    }
+
+    /// The number of unit cells inside the box.
+    pub fn volume(&self) -> i128 {
+        if self.is_empty() {
+            return 0;
+        }
+
+        self.axes
+            .iter()
+            .map(|r| (r.end - r.start).as_i128())
+            .product()
+    }
+
+    /// Compute the intersection of two boxes, or None if they do not intersect.
+    pub fn intersect(&self, other: &Self) -> Option<Self> {
+        let mut axes = self.axes.clone();
+        for (axis, other) in axes.iter_mut().zip(&other.axes) {
+            *axis = intervals::intersect(axis, other)?;
+        }
+        Some(Self { axes })
+    }
+
+    /// Remove another box from this one, splitting what is left into disjoint boxes.
+    /// At most `2 * D` boxes are returned.
+    #[cfg(test)]
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        let Some(inter) = self.intersect(other) else {
+            return vec![self.clone()];
+        };
+
+        // Cut the slices before and after the intersection along each axis,
+        // then only keep the middle slice to cut it along the next axes
+        let mut res = vec![];
+        let mut rest = self.clone();
+        for (axis, inter) in inter.axes.iter().enumerate() {
+            let range = rest.axes[axis].clone();
+
+            if range.start < inter.start {
+                let mut before = rest.clone();
+                before.axes[axis] = range.start..inter.start;
+                res.push(before);
+            }
+
+            if inter.end < range.end {
+                let mut after = rest.clone();
+                after.axes[axis] = inter.end..range.end;
+                res.push(after);
+            }
+
+            rest.axes[axis] = inter.clone();
+        }
+
+        res
+    }
+}
+
+/// A union of boxes that can be added or removed in any order,
+/// computing the volume by inclusion–exclusion.
+///
+/// Each added box is counted once positively, and each intersection with
+/// a stored box is counted with the opposite sign to not be counted twice.
+/// Identical boxes share their count, which is dropped once it reaches 0.
+#[derive(Debug, Clone, Default)]
+pub struct SignedBoxes<T, const D: usize> {
+    counts: HashMap<Aabb<T, D>, i64>,
+}
+
+impl<T: Int + Hash, const D: usize> SignedBoxes<T, D> {
+    pub fn new() -> Self {
+        Self {
+            counts: HashMap::new(),
+        }
+    }
+
+    /// Add the cells of the box to the union.
+    pub fn add(&mut self, b: &Aabb<T, D>) {
+        self.cancel_intersections(b);
+        if !b.is_empty() {
+            self.update(b.clone(), 1);
+        }
+    }
+
+    /// Remove the cells of the box from the union.
+    pub fn remove(&mut self, b: &Aabb<T, D>) {
+        self.cancel_intersections(b);
+    }
+
+    /// The number of unit cells in the union.
+    pub fn volume(&self) -> i128 {
+        self.counts
+            .iter()
+            .map(|(b, &count)| b.volume() * count as i128)
+            .sum()
+    }
+
+    /// The number of distinct boxes stored to compute the volume.
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Cancel the current volume inside the box.
+    fn cancel_intersections(&mut self, b: &Aabb<T, D>) {
+        let cancels: Vec<_> = self
+            .counts
+            .iter()
+            .filter_map(|(other, &count)| other.intersect(b).map(|inter| (inter, -count)))
+            .collect();
+        for (inter, count) in cancels {
+            self.update(inter, count);
+        }
+    }
+
+    /// Change the count of a box, forgetting it once it cancels out.
+    fn update(&mut self, b: Aabb<T, D>, delta: i64) {
+        match self.counts.entry(b) {
+            Entry::Occupied(mut entry) => {
+                *entry.get_mut() += delta;
+                if *entry.get() == 0 {
+                    entry.remove();
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(delta);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use crate::utils::test_rng::{Rng, SEED};
+
+    use super::{Aabb, SignedBoxes};
+
+    fn random_box<const D: usize>(rng: &mut Rng) -> Aabb<i32, D> {
+        Aabb::new([(); D].map(|_| rng.interval(-5..5, 1..6)))
+    }
+
+    fn cells(b: &Aabb<i32, 3>) -> impl Iterator<Item = [i32; 3]> + '_ {
+        let [x, y, z] = b.axes.clone();
+        x.flat_map(move |x| {
+            let z = z.clone();
+            y.clone()
+                .flat_map(move |y| z.clone().map(move |z| [x, y, z]))
+        })
+    }
+
+    #[test]
+    fn intersect_subtract() {
+        let a = Aabb::new([0..4, 0..4]);
+        let b = Aabb::new([2..6, 1..2]);
+
+        assert_eq!(Some(Aabb::new([2..4, 1..2])), a.intersect(&b));
+        assert_eq!(None, a.intersect(&Aabb::new([4..6, 0..4])));
+
+        let pieces = a.subtract(&b);
+        assert_eq!(
+            vec![
+                Aabb::new([0..2, 0..4]),
+                Aabb::new([2..4, 0..1]),
+                Aabb::new([2..4, 2..4])
+            ],
+            pieces
+        );
+        assert_eq!(vec![a.clone()], a.subtract(&Aabb::new([4..6, 0..4])));
+        assert!(a.subtract(&a).is_empty());
+    }
+
+    #[test]
+    fn random_subtract() {
+        let mut rng = Rng::new(SEED);
+        for _ in 0..500 {
+            let (a, b) = (random_box::<3>(&mut rng), random_box::<3>(&mut rng));
+            let pieces = a.subtract(&b);
+
+            // The pieces are disjoint, and contain the cells of `a` not in `b`
+            let expected: HashSet<_> = cells(&a).filter(|c| !cells(&b).any(|o| o == *c)).collect();
+            let got: Vec<_> = pieces.iter().flat_map(cells).collect();
+            assert_eq!(expected.len(), got.len());
+            assert_eq!(expected, got.into_iter().collect());
+        }
+    }
+
+    /// Compare the inclusion–exclusion union with a brute-force set of cells.
+    #[test]
+    fn random_steps() {
+        let mut rng = Rng::new(SEED);
+        for _ in 0..200 {
+            let mut signed = SignedBoxes::new();
+            let mut expected = HashSet::new();
+
+            for _ in 0..rng.range(1..12) {
+                let b = random_box(&mut rng);
+                if rng.range(0..3) == 0 {
+                    signed.remove(&b);
+                    cells(&b).for_each(|c| {
+                        expected.remove(&c);
+                    });
+                } else {
+                    signed.add(&b);
+                    expected.extend(cells(&b));
+                }
+
+                assert_eq!(expected.len() as i128, signed.volume());
+            }
+        }
+    }
+
+    #[test]
+    fn cancel_out() {
+        let a = Aabb::new([0..4, 0..4]);
+        let b = Aabb::new([2..6, 2..6]);
+
+        let mut signed = SignedBoxes::new();
+        for _ in 0..10 {
+            signed.add(&a);
+        }
+        assert_eq!(1, signed.len());
+
+        signed.add(&b);
+        assert_eq!(3, signed.len());
+        assert_eq!(28, signed.volume());
+
+        signed.remove(&b);
+        signed.remove(&a);
+        assert!(signed.is_empty());
+    }
+}
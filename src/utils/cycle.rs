@@ -0,0 +1,155 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    ops::{Add, Mul, Sub},
+};
+
+/// A cycle in a sequence of states: the state at step `start + period`
+/// is the same as the one at step `start`, and the sequence repeats from there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    /// The first step of the first cycle
+    pub start: usize,
+    /// The number of steps in a cycle
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The step before the end of the first cycle that has the same state as step `n`.
+    pub fn equivalent_step(&self, n: u64) -> usize {
+        if n < (self.start + self.period) as u64 {
+            n as usize
+        } else {
+            self.start + ((n - self.start as u64) % self.period as u64) as usize
+        }
+    }
+
+    /// Extrapolate a metric accumulated at each step to step `n`, assuming it increases
+    /// by the same amount at each cycle (e.g. a tower height, a score, a distance).
+    ///
+    /// `history[i]` must be the metric value at step `i`, up to at least step `start + period`.
+    pub fn extrapolate<T>(&self, history: &[T], n: u64) -> T
+    where
+        T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + TryFrom<u64>,
+    {
+        let end = self.start + self.period;
+        assert!(
+            history.len() > end,
+            "The history does not contain a full cycle"
+        );
+
+        let equivalent = self.equivalent_step(n);
+        if equivalent as u64 == n {
+            return history[equivalent];
+        }
+
+        let nb_cycles = (n - self.start as u64) / self.period as u64;
+        let nb_cycles = T::try_from(nb_cycles).unwrap_or_else(|_| panic!("Too many cycles"));
+        history[equivalent] + nb_cycles * (history[end] - history[self.start])
+    }
+}
+
+/// Find a cycle in a simulation by remembering a fingerprint of each step state.
+///
+/// Useful when the state is too big to be compared directly, or when a partial
+/// state (e.g. only the top rows of a tower) is enough to know that it repeats.
+pub struct CycleFinder<K> {
+    seen: HashMap<K, usize>,
+    step: usize,
+}
+
+impl<K: Hash + Eq> CycleFinder<K> {
+    pub fn new() -> Self {
+        Self {
+            seen: HashMap::new(),
+            step: 0,
+        }
+    }
+
+    /// Record the fingerprint of the next step state, starting at step 0.
+    /// Return the cycle if the same fingerprint has already been recorded.
+    pub fn push(&mut self, key: K) -> Option<Cycle> {
+        let step = self.step;
+        self.step += 1;
+
+        let start = *self.seen.entry(key).or_insert(step);
+        (start != step).then(|| Cycle {
+            start,
+            period: step - start,
+        })
+    }
+}
+
+impl<K: Hash + Eq> Default for CycleFinder<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::{Cycle, CycleFinder};
+
+    /// Find the cycle by remembering every state
+    fn brute_force(x0: u64, f: impl Fn(&u64) -> u64) -> Cycle {
+        let mut seen = HashMap::new();
+        let mut x = x0;
+        for step in 0.. {
+            if let Some(start) = seen.insert(x, step) {
+                return Cycle {
+                    start,
+                    period: step - start,
+                };
+            }
+            x = f(&x);
+        }
+        unreachable!()
+    }
+
+    #[test]
+    fn detect() {
+        for modulo in 2..200 {
+            for x0 in [0, 1, 7, 42] {
+                let f = |x: &u64| (x * x + 1) % modulo;
+                let expected = brute_force(x0, f);
+
+                let mut finder = CycleFinder::new();
+                let mut x = x0;
+                let found = loop {
+                    if let Some(cycle) = finder.push(x) {
+                        break cycle;
+                    }
+                    x = f(&x);
+                };
+                assert_eq!(expected, found);
+            }
+        }
+    }
+
+    #[test]
+    fn extrapolate() {
+        // A metric that increases by 1, 2 before the cycle, then by 3, 1, 1 forever
+        let increments = [1, 2, 3, 1, 1, 3, 1, 1, 3, 1, 1, 3, 1, 1];
+        let history: Vec<u64> = [0]
+            .into_iter()
+            .chain(increments.iter().scan(0, |sum, inc| {
+                *sum += inc;
+                Some(*sum)
+            }))
+            .collect();
+        let cycle = Cycle {
+            start: 2,
+            period: 3,
+        };
+
+        for n in 0..history.len() {
+            assert_eq!(history[n], cycle.extrapolate(&history[..6], n as u64));
+        }
+        assert_eq!(
+            3 + 5 * 1_000_000,
+            cycle.extrapolate(&history, 2 + 3 * 1_000_000)
+        );
+    }
+}
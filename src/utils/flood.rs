@@ -0,0 +1,273 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+use super::{CheckedYX, Grid};
+
+/// A connected region of nodes, found by a flood fill.
+pub struct Region<N> {
+    /// The nodes inside the region, in the order they were filled.
+    pub nodes: Vec<N>,
+    /// The edges going from a node inside the region to a neighbor outside of it.
+    /// A same outside node may appear multiple times, once per inside neighbor.
+    pub boundary: Vec<(N, N)>,
+}
+
+impl<N> Region<N> {
+    /// The number of nodes inside the region.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    #[cfg(test)]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+}
+
+/// Fill the region connected to the start nodes, only going through the nodes inside it.
+///
+/// Start nodes that are not inside are ignored.
+pub fn flood_fill<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut is_inside: impl FnMut(&N) -> bool,
+) -> Region<N>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut visited = HashSet::new();
+    fill(starts, &mut neighbors, &mut is_inside, &mut |n| {
+        visited.insert(n.clone())
+    })
+}
+
+/// The connected components of a graph.
+pub struct Components<N> {
+    #[cfg(test)]
+    ids: HashMap<N, usize>,
+    regions: Vec<Region<N>>,
+}
+
+impl<N: Eq + Hash> Components<N> {
+    /// The id of the component containing the node, if it is inside one.
+    #[cfg(test)]
+    pub fn id(&self, node: &N) -> Option<usize> {
+        self.ids.get(node).copied()
+    }
+
+    /// The components, indexed by their ids.
+    #[cfg(test)]
+    pub fn regions(&self) -> &[Region<N>] {
+        &self.regions
+    }
+
+    /// The number of nodes of each component, indexed by their ids.
+    pub fn sizes(&self) -> impl Iterator<Item = usize> + '_ {
+        self.regions.iter().map(Region::len)
+    }
+
+    /// The number of components.
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+}
+
+/// Split the nodes that are inside into connected components.
+/// Component ids are given in the order their first node appear in `nodes`.
+pub fn components<N, I>(
+    nodes: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut is_inside: impl FnMut(&N) -> bool,
+) -> Components<N>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut ids = HashMap::new();
+    let mut regions = vec![];
+
+    for node in nodes {
+        if ids.contains_key(&node) || !is_inside(&node) {
+            continue;
+        }
+
+        let id = regions.len();
+        let region = fill([node], &mut neighbors, &mut is_inside, &mut |n| {
+            ids.insert(n.clone(), id).is_none()
+        });
+        regions.push(region);
+    }
+
+    Components {
+        #[cfg(test)]
+        ids,
+        regions,
+    }
+}
+
+impl<T> Grid<T> {
+    /// The positions above, below, left and right of the given one, if inside the grid.
+    pub fn neighbors4(&self, pos: CheckedYX) -> impl Iterator<Item = CheckedYX> {
+        [
+            self.top(pos),
+            self.bottom(pos),
+            self.left(pos),
+            self.right(pos),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// Fill the region of cells connected to the start position
+    /// (horizontally or vertically) for which the predicate is true.
+    #[cfg(test)]
+    pub fn flood_fill(
+        &self,
+        start: CheckedYX,
+        mut is_inside: impl FnMut(&T) -> bool,
+    ) -> Region<CheckedYX> {
+        flood_fill(
+            [start],
+            |&pos| self.neighbors4(pos),
+            |&pos| is_inside(self.get(pos)),
+        )
+    }
+
+    /// Split the cells for which the predicate is true into regions
+    /// connected horizontally or vertically.
+    pub fn components(&self, mut is_inside: impl FnMut(&T) -> bool) -> Components<CheckedYX> {
+        components(
+            self.coordinates(),
+            |&pos| self.neighbors4(pos),
+            |&pos| is_inside(self.get(pos)),
+        )
+    }
+}
+
+/// Depth-first fill from the start nodes.
+/// `visit` marks a node as visited and returns whether it was not already.
+fn fill<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    neighbors: &mut impl FnMut(&N) -> I,
+    is_inside: &mut impl FnMut(&N) -> bool,
+    visit: &mut impl FnMut(&N) -> bool,
+) -> Region<N>
+where
+    N: Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut region = Region {
+        nodes: vec![],
+        boundary: vec![],
+    };
+
+    let mut stack = vec![];
+    for start in starts {
+        if is_inside(&start) && visit(&start) {
+            stack.push(start);
+        }
+    }
+
+    while let Some(node) = stack.pop() {
+        for next in neighbors(&node) {
+            if !is_inside(&next) {
+                region.boundary.push((node.clone(), next));
+            } else if visit(&next) {
+                stack.push(next);
+            }
+        }
+        region.nodes.push(node);
+    }
+
+    region
+}
+
+#[cfg(test)]
+mod test {
+    use crate::utils::{CheckedYX, Grid};
+
+    use super::{components, flood_fill};
+
+    /// ```text
+    /// ##..#
+    /// #..##
+    /// ..#..
+    /// ```
+    fn grid() -> Grid<bool> {
+        Grid::from_str_map(["##..#", "#..##", "..#.."].into_iter(), |c| c == '#').unwrap()
+    }
+
+    #[test]
+    fn grid_flood_fill() {
+        let g = grid();
+        let start = CheckedYX::new(&g, (0, 2)).unwrap();
+        let region = g.flood_fill(start, |&b| !b);
+
+        assert_eq!(6, region.len());
+        assert_eq!(9, region.boundary.len());
+        assert!(g.flood_fill(start, |&b| b).is_empty());
+    }
+
+    #[test]
+    fn grid_components() {
+        let g = grid();
+        let comps = g.components(|&b| b);
+
+        assert_eq!(3, comps.len());
+        assert_eq!(vec![3, 3, 1], comps.sizes().collect::<Vec<_>>());
+        assert_eq!(Some(1), comps.id(&CheckedYX::new(&g, (1, 3)).unwrap()));
+        assert_eq!(None, comps.id(&CheckedYX::new(&g, (1, 1)).unwrap()));
+    }
+
+    #[test]
+    fn voxels() {
+        // A 3x3x3 cube with a hole in its center
+        let cube = |&[x, y, z]: &[i8; 3]| [x, y, z].iter().all(|c| (0..3).contains(c));
+        let is_droplet = |p: &[i8; 3]| cube(p) && *p != [1, 1, 1];
+        let neighbors = |&[x, y, z]: &[i8; 3]| {
+            [
+                [x - 1, y, z],
+                [x + 1, y, z],
+                [x, y - 1, z],
+                [x, y + 1, z],
+                [x, y, z - 1],
+                [x, y, z + 1],
+            ]
+        };
+
+        // Exterior surface, filling the air around the cube
+        let in_bounds = |&[x, y, z]: &[i8; 3]| [x, y, z].iter().all(|c| (-1..4).contains(c));
+        let air = flood_fill(
+            [[-1, -1, -1]],
+            |p| neighbors(p).into_iter().filter(in_bounds),
+            |p| !is_droplet(p),
+        );
+        assert_eq!(6 * 9, air.boundary.len());
+
+        // Total surface, including the hole
+        let droplets = (0..27).map(|i| [i % 3, i / 3 % 3, i / 9]);
+        let comps = components(droplets, neighbors, is_droplet);
+        assert_eq!(1, comps.len());
+        assert_eq!(26, comps.regions()[0].len());
+        assert_eq!(6 * 9 + 6, comps.regions()[0].boundary.len());
+    }
+
+    #[test]
+    fn graph() {
+        let edges = [(0, 1), (1, 2), (3, 4)];
+        let neighbors = |&n: &u8| {
+            edges
+                .iter()
+                .filter_map(move |&(a, b)| (a == n).then_some(b).or((b == n).then_some(a)))
+        };
+
+        let comps = components(0..6, neighbors, |_| true);
+        assert_eq!(vec![3, 2, 1], comps.sizes().collect::<Vec<_>>());
+        assert_eq!(comps.id(&0), comps.id(&2));
+        assert_ne!(comps.id(&0), comps.id(&3));
+    }
+}
@@ -0,0 +1,419 @@
+use std::{
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+use super::intervals::Int;
+
+/// A 2D vector, also used as a point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Vec2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+/// A 3D vector, also used as a point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Vec3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Vec2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+}
+
+impl<T> Vec3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+}
+
+/// The absolute difference between two integers, without requiring a signed type.
+fn abs_diff<T: Int>(a: T, b: T) -> T {
+    if a >= b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+/// The sign of an integer: -1, 0 or 1.
+fn signum<T: Int + Neg<Output = T>>(v: T) -> T {
+    match v.cmp(&T::ZERO) {
+        std::cmp::Ordering::Less => -T::ONE,
+        std::cmp::Ordering::Equal => T::ZERO,
+        std::cmp::Ordering::Greater => T::ONE,
+    }
+}
+
+/// Implement the component-wise operations shared by all vector types.
+macro_rules! impl_vec {
+    ($vec:ident { $($f:ident),+ }) => {
+        impl<T: Add<Output = T>> Add for $vec<T> {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self { $($f: self.$f + rhs.$f),+ }
+            }
+        }
+
+        impl<T: Sub<Output = T>> Sub for $vec<T> {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self { $($f: self.$f - rhs.$f),+ }
+            }
+        }
+
+        impl<T: Copy + Mul<Output = T>> Mul<T> for $vec<T> {
+            type Output = Self;
+
+            fn mul(self, rhs: T) -> Self {
+                Self { $($f: self.$f * rhs),+ }
+            }
+        }
+
+        impl<T: Neg<Output = T>> Neg for $vec<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self { $($f: -self.$f),+ }
+            }
+        }
+
+        impl<T: Copy + Add<Output = T>> AddAssign for $vec<T> {
+            fn add_assign(&mut self, rhs: Self) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl<T: Copy + Sub<Output = T>> SubAssign for $vec<T> {
+            fn sub_assign(&mut self, rhs: Self) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl<T: Int> $vec<T> {
+            /// The distance when only moving along the axes (taxicab distance).
+            pub fn manhattan(self, other: Self) -> T {
+                T::ZERO $(+ abs_diff(self.$f, other.$f))+
+            }
+
+            /// Clamp each component between the ones of `min` and `max`.
+            pub fn clamp(self, min: Self, max: Self) -> Self {
+                Self { $($f: self.$f.clamp(min.$f, max.$f)),+ }
+            }
+
+            /// Check that each component is between the ones of `min` and `max` (both included).
+            pub fn in_bounds(self, min: Self, max: Self) -> bool {
+                self.clamp(min, max) == self
+            }
+        }
+
+        /// Parse comma-separated components, e.g. `1,-2` or `1,-2,3`.
+        impl<T: FromStr> FromStr for $vec<T> {
+            type Err = ();
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                let mut parts = s.trim().split(',').map(|p| p.trim().parse().map_err(|_| ()));
+                let v = Self { $($f: parts.next().ok_or(())??),+ };
+
+                match parts.next() {
+                    None => Ok(v),
+                    Some(_) => Err(()),
+                }
+            }
+        }
+    };
+}
+
+impl_vec!(Vec2 { x, y });
+impl_vec!(Vec3 { x, y, z });
+
+impl<T: Int> Vec2<T> {
+    /// The distance when also moving diagonally (king moves distance).
+    pub fn chebyshev(self, other: Self) -> T {
+        abs_diff(self.x, other.x).max(abs_diff(self.y, other.y))
+    }
+}
+
+impl<T: Int + Neg<Output = T>> Vec2<T> {
+    /// The sign of each component: a step of at most 1 on each axis towards the vector.
+    pub fn signum(self) -> Self {
+        Self::new(signum(self.x), signum(self.y))
+    }
+
+    /// The 4 orthogonally adjacent positions.
+    pub fn neighbors4(self) -> impl Iterator<Item = Self> {
+        Dir::ALL.into_iter().map(move |dir| self + dir.delta())
+    }
+
+    /// The 8 orthogonally and diagonally adjacent positions.
+    #[cfg(test)]
+    pub fn neighbors8(self) -> impl Iterator<Item = Self> {
+        let (zero, one) = (T::ZERO, T::ONE);
+        [
+            (-one, -one),
+            (zero, -one),
+            (one, -one),
+            (-one, zero),
+            (one, zero),
+            (-one, one),
+            (zero, one),
+            (one, one),
+        ]
+        .into_iter()
+        .map(move |(x, y)| self + Vec2::new(x, y))
+    }
+}
+
+impl<T: Int + Neg<Output = T>> Vec3<T> {
+    /// The 6 positions sharing a face with this one.
+    pub fn neighbors6(self) -> impl Iterator<Item = Self> {
+        let (zero, one) = (T::ZERO, T::ONE);
+        [
+            (one, zero, zero),
+            (-one, zero, zero),
+            (zero, one, zero),
+            (zero, -one, zero),
+            (zero, zero, one),
+            (zero, zero, -one),
+        ]
+        .into_iter()
+        .map(move |(x, y, z)| self + Vec3::new(x, y, z))
+    }
+}
+
+/// A 2D direction, with the Y axis pointing down as in grids and screens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dir {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Dir {
+    /// All directions, in clockwise order.
+    pub const ALL: [Dir; 4] = [Dir::Up, Dir::Right, Dir::Down, Dir::Left];
+
+    /// The direction after a quarter turn clockwise.
+    pub const fn turn_right(self) -> Self {
+        match self {
+            Dir::Up => Dir::Right,
+            Dir::Right => Dir::Down,
+            Dir::Down => Dir::Left,
+            Dir::Left => Dir::Up,
+        }
+    }
+
+    /// The direction after a quarter turn counter-clockwise.
+    pub const fn turn_left(self) -> Self {
+        self.turn_right().reverse()
+    }
+
+    /// The opposite direction.
+    pub const fn reverse(self) -> Self {
+        self.turn_right().turn_right()
+    }
+
+    /// The unit vector of a step in this direction.
+    pub fn delta<T: Int + Neg<Output = T>>(self) -> Vec2<T> {
+        match self {
+            Dir::Up => Vec2::new(T::ZERO, -T::ONE),
+            Dir::Right => Vec2::new(T::ONE, T::ZERO),
+            Dir::Down => Vec2::new(T::ZERO, T::ONE),
+            Dir::Left => Vec2::new(-T::ONE, T::ZERO),
+        }
+    }
+}
+
+/// Parse a direction from its initial (`U`, `R`, `D`, `L`) or its arrow (`^`, `>`, `v`, `<`).
+impl TryFrom<char> for Dir {
+    type Error = ();
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        Ok(match c {
+            'U' | '^' => Dir::Up,
+            'R' | '>' => Dir::Right,
+            'D' | 'v' => Dir::Down,
+            'L' | '<' => Dir::Left,
+            _ => return Err(()),
+        })
+    }
+}
+
+/// A rotation in 3D space by multiples of a quarter turn, stored as a matrix.
+///
+/// Each row has a single non-zero coefficient, either 1 or -1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rotation([[i8; 3]; 3]);
+
+impl Rotation {
+    pub const IDENTITY: Self = Self([[1, 0, 0], [0, 1, 0], [0, 0, 1]]);
+
+    /// The 24 orientations a cube can be rotated into, starting with the identity.
+    pub fn all() -> [Self; 24] {
+        // Every signed permutation matrix with a determinant of 1
+        const PERMS: [([usize; 3], i8); 6] = [
+            ([0, 1, 2], 1),
+            ([1, 2, 0], 1),
+            ([2, 0, 1], 1),
+            ([0, 2, 1], -1),
+            ([2, 1, 0], -1),
+            ([1, 0, 2], -1),
+        ];
+
+        let mut rotations = [Self::IDENTITY; 24];
+        let mut i = 0;
+        for (perm, parity) in PERMS {
+            for signs in 0..8 {
+                let sign = |axis: usize| if signs >> axis & 1 == 0 { 1 } else { -1 };
+                if sign(0) * sign(1) * sign(2) != parity {
+                    continue;
+                }
+
+                let mut m = [[0; 3]; 3];
+                for (row, (&col, m_row)) in perm.iter().zip(&mut m).enumerate() {
+                    m_row[col] = sign(row);
+                }
+                rotations[i] = Self(m);
+                i += 1;
+            }
+        }
+
+        rotations
+    }
+
+    /// Rotate a vector.
+    pub fn apply<T: Int + Neg<Output = T>>(&self, v: Vec3<T>) -> Vec3<T> {
+        let [x, y, z] = self.0.map(|row| {
+            [v.x, v.y, v.z]
+                .into_iter()
+                .zip(row)
+                .map(|(c, m)| match m {
+                    1 => c,
+                    -1 => -c,
+                    _ => T::ZERO,
+                })
+                .fold(T::ZERO, |acc, c| acc + c)
+        });
+        Vec3::new(x, y, z)
+    }
+
+    /// The rotation applying `self` then `other`.
+    pub fn then(&self, other: &Self) -> Self {
+        let mut m = [[0; 3]; 3];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = (0..3).map(|k| other.0[i][k] * self.0[k][j]).sum();
+            }
+        }
+        Self(m)
+    }
+
+    /// The rotation undoing this one.
+    #[cfg(test)]
+    pub fn inverse(&self) -> Self {
+        let mut m = [[0; 3]; 3];
+        for (i, row) in m.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = self.0[j][i];
+            }
+        }
+        Self(m)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use super::{Dir, Rotation, Vec2, Vec3};
+
+    #[test]
+    fn arithmetic() {
+        let a = Vec2::new(1, -2);
+        let b = Vec2::new(4, 2);
+
+        assert_eq!(Vec2::new(5, 0), a + b);
+        assert_eq!(Vec2::new(-3, -4), a - b);
+        assert_eq!(Vec2::new(3, -6), a * 3);
+        assert_eq!(Vec2::new(-1, 2), -a);
+        assert_eq!(7, a.manhattan(b));
+        assert_eq!(4, a.chebyshev(b));
+        assert_eq!(Vec2::new(-1, -1), (a - b).signum());
+        assert_eq!(Vec2::new(1, 0), a.clamp(Vec2::new(0, 0), Vec2::new(3, 3)));
+
+        // Unsigned vectors still have distances
+        assert_eq!(5u8, Vec3::new(1, 5, 2).manhattan(Vec3::new(2, 3, 4)));
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(Ok(Vec2::new(3, -4)), "3,-4".parse());
+        assert_eq!(Ok(Vec3::new(1, 2, 3)), " 1, 2,3".parse());
+        assert_eq!(Err(()), "1,2,3".parse::<Vec2<i32>>());
+        assert_eq!(Err(()), "1,2".parse::<Vec3<i32>>());
+        assert_eq!(Err(()), "1,a".parse::<Vec2<i32>>());
+    }
+
+    #[test]
+    fn neighbors() {
+        let p = Vec2::new(0, 0);
+        assert_eq!(4, p.neighbors4().filter(|n| n.manhattan(p) == 1).count());
+        assert_eq!(8, p.neighbors8().collect::<HashSet<_>>().len());
+        assert!(p.neighbors8().all(|n| n.chebyshev(p) == 1));
+
+        let p = Vec3::new(1, 1, 1);
+        assert!(p.neighbors6().all(|n| n.manhattan(p) == 1));
+        assert_eq!(6, p.neighbors6().collect::<HashSet<_>>().len());
+    }
+
+    #[test]
+    fn directions() {
+        for dir in Dir::ALL {
+            assert_eq!(dir, dir.turn_right().turn_left());
+            assert_eq!(dir.reverse(), dir.turn_left().turn_left());
+            assert_eq!(Vec2::new(0, 0), dir.delta::<i32>() + dir.reverse().delta());
+        }
+
+        assert_eq!(Dir::Right, Dir::Up.turn_right());
+        assert_eq!(Vec2::new(0, -1), Dir::Up.delta());
+        assert_eq!(Ok(Dir::Left), Dir::try_from('<'));
+        assert_eq!(Ok(Dir::Down), Dir::try_from('D'));
+    }
+
+    #[test]
+    fn rotations() {
+        let all = Rotation::all();
+        assert_eq!(Rotation::IDENTITY, all[0]);
+
+        // All distinct, and a group: closed under composition and inverse
+        let set: HashSet<_> = all.iter().copied().collect();
+        assert_eq!(24, set.len());
+        for a in &all {
+            assert_eq!(Rotation::IDENTITY, a.then(&a.inverse()));
+            for b in &all {
+                assert!(set.contains(&a.then(b)));
+            }
+        }
+
+        // Rotating an asymmetric vector gives 24 distinct vectors of the same length
+        let v = Vec3::new(1, 2, 3);
+        let rotated: HashSet<_> = all.iter().map(|r| r.apply(v)).collect();
+        assert_eq!(24, rotated.len());
+        for r in &all {
+            assert_eq!(v, r.inverse().apply(r.apply(v)));
+            assert_eq!(6, r.apply(v).manhattan(Vec3::new(0, 0, 0)));
+        }
+
+        // `then` applies the rotations in order
+        let (a, b) = (all[5], all[17]);
+        assert_eq!(b.apply(a.apply(v)), a.then(&b).apply(v));
+    }
+}
@@ -1,8 +1,4 @@
-use std::{
-    fmt::{Display, Write},
-    ops::Deref,
-    str::FromStr,
-};
+use std::{fmt::Display, ops::Deref, str::FromStr};
 
 #[derive(Clone)]
 pub struct Grid<T> {
@@ -17,7 +13,7 @@ pub type YX = (usize, usize);
 ///
 /// ** WARNING: Currently does not link a position to a specific grid
 /// (can UB when used with a different grid than created with) **
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct CheckedYX(YX);
 
 impl CheckedYX {
@@ -49,7 +45,61 @@ impl Deref for CheckedYX {
     }
 }
 
+/// A symmetry of a rectangle, used to rotate or flip a grid by mapping its positions.
+///
+/// Rotating by 180° and mirroring the columns are done by reversing cells instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transform {
+    /// Rotate by 90° clockwise
+    RotateCw,
+    /// Rotate by 90° counter-clockwise (270° clockwise)
+    RotateCcw,
+    /// Mirror the rows (top <-> bottom)
+    FlipVertical,
+    /// Mirror along the top-left to bottom-right diagonal
+    Transpose,
+}
+
+impl Transform {
+    /// Whether the transform swaps the height and width of the grid.
+    pub const fn swaps_dims(self) -> bool {
+        matches!(self, Self::RotateCw | Self::RotateCcw | Self::Transpose)
+    }
+
+    /// Map a position of a `height x width` grid to its position in the transformed grid.
+    pub const fn map_yx(self, (y, x): YX, height: usize, width: usize) -> YX {
+        let (ly, lx) = (height - 1, width - 1);
+        match self {
+            Self::RotateCw => (x, ly - y),
+            Self::RotateCcw => (lx - x, y),
+            Self::FlipVertical => (ly - y, x),
+            Self::Transpose => (x, y),
+        }
+    }
+
+    /// The transform that cancels this one.
+    pub const fn inverse(self) -> Self {
+        match self {
+            Self::RotateCw => Self::RotateCcw,
+            Self::RotateCcw => Self::RotateCw,
+            t => t,
+        }
+    }
+}
+
 impl<T> Grid<T> {
+    /// Create a new grid filled with the given element.
+    pub fn new(height: usize, width: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            data: vec![fill; height * width],
+            width,
+            height,
+        }
+    }
+
     /// Get a cell data
     pub fn get(&self, pos: CheckedYX) -> &T {
         let CheckedYX((y, x)) = pos;
@@ -68,7 +118,7 @@ impl<T> Grid<T> {
 
     pub fn left(&self, pos: CheckedYX) -> Option<CheckedYX> {
         let CheckedYX((y, x)) = pos;
-        (x > 0).then_some(CheckedYX((y, x - 1)))
+        (x > 0).then(|| CheckedYX((y, x - 1)))
     }
 
     pub fn right(&self, pos: CheckedYX) -> Option<CheckedYX> {
@@ -78,7 +128,7 @@ impl<T> Grid<T> {
 
     pub fn top(&self, pos: CheckedYX) -> Option<CheckedYX> {
         let CheckedYX((y, x)) = pos;
-        (y > 0).then_some(CheckedYX((y - 1, x)))
+        (y > 0).then(|| CheckedYX((y - 1, x)))
     }
 
     pub fn bottom(&self, pos: CheckedYX) -> Option<CheckedYX> {
@@ -86,6 +136,34 @@ impl<T> Grid<T> {
         (y + 1 < self.height).then_some(CheckedYX((y + 1, x)))
     }
 
+    /// Get the cells of a row.
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.data[y * self.width..(y + 1) * self.width]
+    }
+
+    /// Get the mutable cells of a row.
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        &mut self.data[y * self.width..(y + 1) * self.width]
+    }
+
+    /// Create an iterator over the rows, from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `max(1)` so that an empty grid does not panic
+        self.data.chunks_exact(self.width.max(1))
+    }
+
+    /// Create an iterator over the cells of a column, from top to bottom.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {x} out of bounds");
+        self.data[x..].iter().step_by(self.width)
+    }
+
+    /// Create an iterator over the columns, from left to right.
+    #[cfg(test)]
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
     /// Create an iterator over the (y, x) coordinates.
     pub fn coordinates(&self) -> impl Iterator<Item = CheckedYX> {
         let height = self.height;
@@ -94,23 +172,82 @@ impl<T> Grid<T> {
     }
 
     /// Parse a 2D string of characters into a grid.
+    ///
+    /// Fail if there are no lines or if they do not all have the same length.
     pub fn from_str_map<'a>(
-        mut lines: impl Iterator<Item = &'a str>,
+        lines: impl Iterator<Item = &'a str>,
         mut map_char: impl FnMut(char) -> T,
-    ) -> Self {
-        // Extract and process the first line to get the width
-        let mut data: Vec<_> = lines.next().unwrap().chars().map(&mut map_char).collect();
-        let width = data.len();
+    ) -> Result<Self, ParseGridError> {
+        Self::try_from_str_map(lines, |c| Some(map_char(c)))
+    }
 
-        // Extract the rest of the grid and add it to the data vector
-        let chars = lines.flat_map(|line| line.chars()).map(map_char);
-        data.extend(chars);
+    /// Parse a 2D string of characters into a grid,
+    /// where characters mapped to None are invalid.
+    ///
+    /// Fail if there are no lines, if they do not all have the same length,
+    /// or at the first invalid character.
+    pub fn try_from_str_map<'a>(
+        lines: impl Iterator<Item = &'a str>,
+        map_char: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, ParseGridError> {
+        Self::parse_with_markers(lines, [], map_char).map(|(grid, [])| grid)
+    }
 
-        let height = data.len() / width;
-        Grid {
-            data,
-            width,
-            height,
+    /// Parse a 2D string of characters into a grid, where each character
+    /// is converted into a cell, failing at the first one that cannot be.
+    pub fn from_chars(data: &str) -> Result<Self, ParseGridError>
+    where
+        T: TryFrom<char>,
+    {
+        Self::try_from_str_map(data.lines(), |c| T::try_from(c).ok())
+    }
+
+    /// Same as [`Grid::try_from_str_map`], but also find the position of the first
+    /// occurrence of each marker character, if any.
+    /// Markers are still mapped into cells like the other characters.
+    pub fn parse_with_markers<'a, const N: usize>(
+        lines: impl Iterator<Item = &'a str>,
+        markers: [char; N],
+        mut map_char: impl FnMut(char) -> Option<T>,
+    ) -> Result<(Self, [Option<CheckedYX>; N]), ParseGridError> {
+        let mut data = vec![];
+        let mut found = [None; N];
+        let mut width = None;
+        let mut height = 0;
+
+        for (y, line) in lines.enumerate() {
+            let row_start = data.len();
+            for (x, c) in line.chars().enumerate() {
+                if let Some(i) = markers.iter().position(|&m| m == c) {
+                    found[i] = found[i].or(Some(CheckedYX((y, x))));
+                }
+
+                let cell = map_char(c).ok_or(ParseGridError::InvalidChar { y, x, c })?;
+                data.push(cell);
+            }
+
+            let row_width = data.len() - row_start;
+            let expected = *width.get_or_insert(row_width);
+            if row_width != expected {
+                return Err(ParseGridError::RaggedRow {
+                    y,
+                    width: row_width,
+                    expected,
+                });
+            }
+            height += 1;
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok((
+                Grid {
+                    data,
+                    width,
+                    height,
+                },
+                found,
+            )),
+            _ => Err(ParseGridError::Empty),
         }
     }
 
@@ -142,33 +279,351 @@ impl<T> Grid<T> {
 
         new_grid
     }
+
+    /// Create a transformed copy of the grid.
+    #[must_use]
+    pub fn transformed(&self, transform: Transform) -> Self
+    where
+        T: Clone,
+    {
+        let (height, width) = self.transformed_dims(transform);
+        let inverse = transform.inverse();
+
+        // For each cell of the new grid, fetch the cell it comes from
+        let data = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (y, x)))
+            .map(|yx| {
+                let (y, x) = inverse.map_yx(yx, height, width);
+                self.data[x + y * self.width].clone()
+            })
+            .collect();
+
+        Self {
+            data,
+            width,
+            height,
+        }
+    }
+
+    /// Transform the grid in-place, without cloning any cell.
+    #[cfg(test)]
+    pub fn transform(&mut self, transform: Transform) {
+        let (height, width) = self.transformed_dims(transform);
+        let inverse = transform.inverse();
+        let old_width = self.width;
+        let src_idx = |i: usize| {
+            let (y, x) = inverse.map_yx((i / width, i % width), height, width);
+            x + y * old_width
+        };
+
+        // Apply the permutation by following each of its cycles,
+        // swapping every cell with the one that must replace it
+        let mut done = vec![false; self.data.len()];
+        for start in 0..self.data.len() {
+            let mut i = start;
+            while !done[i] {
+                done[i] = true;
+                let src = src_idx(i);
+                if src == start {
+                    break;
+                }
+                self.data.swap(i, src);
+                i = src;
+            }
+        }
+
+        self.height = height;
+        self.width = width;
+    }
+
+    /// Rotate the grid by 90° clockwise.
+    #[cfg(test)]
+    pub fn rotate_cw(&mut self) {
+        self.transform(Transform::RotateCw)
+    }
+
+    /// Rotate the grid by 90° counter-clockwise.
+    #[cfg(test)]
+    pub fn rotate_ccw(&mut self) {
+        self.transform(Transform::RotateCcw)
+    }
+
+    /// Rotate the grid by 180°.
+    #[cfg(test)]
+    pub fn rotate_180(&mut self) {
+        self.data.reverse()
+    }
+
+    /// Mirror the columns of the grid (left <-> right).
+    #[cfg(test)]
+    pub fn flip_horizontal(&mut self) {
+        for y in 0..self.height {
+            self.row_mut(y).reverse();
+        }
+    }
+
+    /// Mirror the rows of the grid (top <-> bottom).
+    #[cfg(test)]
+    pub fn flip_vertical(&mut self) {
+        for y in 0..self.height / 2 {
+            let (top, bottom) = self.data.split_at_mut((self.height - 1 - y) * self.width);
+            top[y * self.width..(y + 1) * self.width].swap_with_slice(&mut bottom[..self.width]);
+        }
+    }
+
+    /// Swap the rows and columns of the grid.
+    #[cfg(test)]
+    pub fn transpose(&mut self) {
+        self.transform(Transform::Transpose)
+    }
+
+    /// Copy of the grid rotated by 90° clockwise.
+    #[cfg(test)]
+    #[must_use]
+    pub fn rotated_cw(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.transformed(Transform::RotateCw)
+    }
+
+    /// Copy of the grid rotated by 90° counter-clockwise.
+    #[cfg(test)]
+    #[must_use]
+    pub fn rotated_ccw(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.transformed(Transform::RotateCcw)
+    }
+
+    /// Copy of the grid rotated by 180°.
+    #[cfg(test)]
+    #[must_use]
+    pub fn rotated_180(&self) -> Self
+    where
+        T: Clone,
+    {
+        let mut grid = self.clone();
+        grid.rotate_180();
+        grid
+    }
+
+    /// Copy of the grid with its columns mirrored (left <-> right).
+    #[cfg(test)]
+    #[must_use]
+    pub fn flipped_horizontal(&self) -> Self
+    where
+        T: Clone,
+    {
+        let mut grid = self.clone();
+        grid.flip_horizontal();
+        grid
+    }
+
+    /// Copy of the grid with its rows mirrored (top <-> bottom).
+    #[must_use]
+    pub fn flipped_vertical(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.transformed(Transform::FlipVertical)
+    }
+
+    /// Copy of the grid with its rows and columns swapped.
+    #[must_use]
+    pub fn transposed(&self) -> Self
+    where
+        T: Clone,
+    {
+        self.transformed(Transform::Transpose)
+    }
+
+    /// Borrow a rectangular part of the grid, starting at the given top-left coordinate.
+    ///
+    /// Return None if the part does not fit inside the grid.
+    pub fn view(&self, at: YX, height: usize, width: usize) -> Option<GridView<'_, T>> {
+        let (y0, x0) = at;
+        (y0 + height <= self.height && x0 + width <= self.width).then_some(GridView {
+            grid: self,
+            at,
+            height,
+            width,
+        })
+    }
+
+    /// Mutably borrow a rectangular part of the grid, starting at the given top-left coordinate.
+    ///
+    /// Return None if the part does not fit inside the grid.
+    #[cfg(test)]
+    pub fn view_mut(&mut self, at: YX, height: usize, width: usize) -> Option<GridViewMut<'_, T>> {
+        let (y0, x0) = at;
+        (y0 + height <= self.height && x0 + width <= self.width).then_some(GridViewMut {
+            grid: self,
+            at,
+            height,
+            width,
+        })
+    }
+
+    /// The (height, width) of the grid after the given transform.
+    const fn transformed_dims(&self, transform: Transform) -> (usize, usize) {
+        if transform.swaps_dims() {
+            (self.width, self.height)
+        } else {
+            (self.height, self.width)
+        }
+    }
+}
+
+/// A borrowed rectangular part of a grid.
+///
+/// Coordinates are relative to the top-left corner of the view.
+#[derive(Clone, Copy)]
+pub struct GridView<'a, T> {
+    grid: &'a Grid<T>,
+    at: YX,
+    pub height: usize,
+    pub width: usize,
+}
+
+impl<'a, T> GridView<'a, T> {
+    /// Get a cell data, or None if out of the view bounds.
+    #[cfg(test)]
+    pub fn get(&self, (y, x): YX) -> Option<&'a T> {
+        let (y0, x0) = self.at;
+        (y < self.height && x < self.width)
+            .then(|| &self.grid.data[(x0 + x) + (y0 + y) * self.grid.width])
+    }
+
+    /// Get the cells of a row of the view.
+    pub fn row(&self, y: usize) -> &'a [T] {
+        assert!(y < self.height, "row {y} out of bounds");
+        let (y0, x0) = self.at;
+        &self.grid.row(y0 + y)[x0..x0 + self.width]
+    }
+
+    /// Create an iterator over the rows of the view, from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &'a [T]> + '_ {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    /// Create an iterator over the cells of a column of the view, from top to bottom.
+    #[cfg(test)]
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &'a T> + '_ {
+        assert!(x < self.width, "column {x} out of bounds");
+        (0..self.height).map(move |y| &self.row(y)[x])
+    }
+
+    /// Copy the view into a new grid.
+    pub fn to_grid(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            data: self.rows().flatten().cloned().collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
 }
 
+/// A mutably borrowed rectangular part of a grid.
+///
+/// Coordinates are relative to the top-left corner of the view.
+#[cfg(test)]
+pub struct GridViewMut<'a, T> {
+    grid: &'a mut Grid<T>,
+    at: YX,
+    pub height: usize,
+    pub width: usize,
+}
+
+#[cfg(test)]
+impl<'a, T> GridViewMut<'a, T> {
+    /// Get a mutable cell data, or None if out of the view bounds.
+    pub fn get_mut(&mut self, (y, x): YX) -> Option<&mut T> {
+        let (y0, x0) = self.at;
+        (y < self.height && x < self.width)
+            .then(|| &mut self.grid.data[(x0 + x) + (y0 + y) * self.grid.width])
+    }
+
+    /// Get the mutable cells of a row of the view.
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        assert!(y < self.height, "row {y} out of bounds");
+        let (y0, x0) = self.at;
+        &mut self.grid.row_mut(y0 + y)[x0..x0 + self.width]
+    }
+
+    /// Reborrow as an immutable view.
+    pub fn as_view(&self) -> GridView<'_, T> {
+        GridView {
+            grid: self.grid,
+            at: self.at,
+            height: self.height,
+            width: self.width,
+        }
+    }
+
+    /// Overwrite the view cells with the ones of a grid of the same dimensions.
+    pub fn copy_from(&mut self, other: &Grid<T>)
+    where
+        T: Clone,
+    {
+        assert_eq!((self.height, self.width), (other.height, other.width));
+        for y in 0..self.height {
+            self.row_mut(y).clone_from_slice(other.row(y));
+        }
+    }
+}
+
+/// Parse a grid of decimal digits.
 impl<T> FromStr for Grid<T>
 where
     u32: TryInto<T>,
 {
-    type Err = ();
+    type Err = ParseGridError;
 
     fn from_str(data: &str) -> Result<Self, Self::Err> {
-        let mut lines = data.split_terminator('\n').map(|line| {
-            line.chars()
-                .map(|c| c.to_digit(10).unwrap().try_into().map_err(|_| ()).unwrap())
-        });
+        Self::try_from_str_map(data.lines(), |c| c.to_digit(10)?.try_into().ok())
+    }
+}
 
-        // Extract the first line to get the width
-        let mut data = lines.next().unwrap().collect::<Vec<_>>();
-        let width = data.len();
+/// An error while parsing a grid. Positions start at 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseGridError {
+    /// There are no cells to parse
+    Empty,
+    /// A row does not have the same width as the first one
+    RaggedRow {
+        y: usize,
+        width: usize,
+        expected: usize,
+    },
+    /// A character could not be converted into a cell
+    InvalidChar { y: usize, x: usize, c: char },
+}
 
-        // Extract the rest of the grid
-        data.extend(lines.flatten());
-        let height = data.len() / width;
+impl Display for ParseGridError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "the grid is empty"),
+            Self::RaggedRow { y, width, expected } => write!(
+                f,
+                "row {y} has a width of {width} instead of {expected} like the first row"
+            ),
+            Self::InvalidChar { y, x, c } => {
+                write!(f, "invalid character {c:?} at row {y}, column {x}")
+            }
+        }
+    }
+}
 
-        Ok(Grid {
-            data,
-            width,
-            height,
-        })
+impl std::error::Error for ParseGridError {}
+
+impl<T> AsRef<Grid<T>> for Grid<T> {
+    fn as_ref(&self) -> &Grid<T> {
+        self
     }
 }
 
@@ -177,16 +632,197 @@ where
     T: Display,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut old_y = 0;
-        for pos @ CheckedYX((y, _x)) in self.coordinates() {
-            if y != old_y {
-                f.write_char('\n')?;
-                old_y = y;
+        self.render(|v| v).separator(" ").fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{CheckedYX, Grid, ParseGridError, Transform};
+
+    /// 2x3 grid:
+    /// ```text
+    /// 0 1 2
+    /// 3 4 5
+    /// ```
+    fn grid() -> Grid<u8> {
+        Grid {
+            data: vec![0, 1, 2, 3, 4, 5],
+            width: 3,
+            height: 2,
+        }
+    }
+
+    const ALL: [Transform; 4] = [
+        Transform::RotateCw,
+        Transform::RotateCcw,
+        Transform::FlipVertical,
+        Transform::Transpose,
+    ];
+
+    #[test]
+    fn transformed() {
+        let g = grid();
+        let expected: [(usize, usize, [u8; 6]); 4] = [
+            (3, 2, [3, 0, 4, 1, 5, 2]),
+            (3, 2, [2, 5, 1, 4, 0, 3]),
+            (2, 3, [3, 4, 5, 0, 1, 2]),
+            (3, 2, [0, 3, 1, 4, 2, 5]),
+        ];
+
+        for (t, (height, width, data)) in ALL.into_iter().zip(expected) {
+            let r = g.transformed(t);
+            assert_eq!((height, width, &data[..]), (r.height, r.width, &r.data[..]));
+        }
+
+        assert_eq!(vec![5, 4, 3, 2, 1, 0], g.rotated_180().data);
+        assert_eq!(vec![2, 1, 0, 5, 4, 3], g.flipped_horizontal().data);
+        assert_eq!(g.rotated_180().data, g.rotated_cw().rotated_cw().data);
+    }
+
+    #[test]
+    fn transform_in_place() {
+        let mut g = Grid {
+            data: (0..20).collect(),
+            width: 5,
+            height: 4,
+        };
+
+        for t in ALL {
+            let mut r = g.clone();
+            r.transform(t);
+            let expected = g.transformed(t);
+            assert_eq!(
+                (expected.height, expected.width, &expected.data),
+                (r.height, r.width, &r.data)
+            );
+        }
+
+        let mut r = g.clone();
+        r.rotate_ccw();
+        assert_eq!(g.rotated_ccw().data, r.data);
+        r = g.clone();
+        r.transpose();
+        assert_eq!(g.transposed().data, r.data);
+        r = g.clone();
+        r.flip_vertical();
+        assert_eq!(g.flipped_vertical().data, r.data);
+
+        // 4 rotations go back to the original grid
+        let original = g.clone();
+        for _ in 0..4 {
+            g.rotate_cw();
+        }
+        assert_eq!(original.data, g.data);
+    }
+
+    #[test]
+    fn map_yx() {
+        let g = grid();
+        for t in ALL {
+            let r = g.transformed(t);
+            for y in 0..g.height {
+                for x in 0..g.width {
+                    let (ry, rx) = t.map_yx((y, x), g.height, g.width);
+                    assert_eq!(g.row(y)[x], r.row(ry)[rx]);
+                    assert_eq!((y, x), t.inverse().map_yx((ry, rx), r.height, r.width));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rows_columns() {
+        let g = grid();
+        assert_eq!(
+            vec![&[0, 1, 2][..], &[3, 4, 5]],
+            g.rows().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![vec![0, 3], vec![1, 4], vec![2, 5]],
+            g.columns()
+                .map(|c| c.copied().collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn views() {
+        let mut g = grid();
+        assert!(g.view((1, 1), 2, 1).is_none());
+
+        let v = g.view((0, 1), 2, 2).unwrap();
+        assert_eq!(Some(&4), v.get((1, 0)));
+        assert_eq!(None, v.get((0, 2)));
+        assert_eq!(&[4, 5], v.row(1));
+        assert_eq!(vec![&2, &5], v.column(1).collect::<Vec<_>>());
+        assert_eq!(vec![1, 2, 4, 5], v.to_grid().data);
+
+        let mut v = g.view_mut((1, 0), 1, 2).unwrap();
+        *v.get_mut((0, 1)).unwrap() = 9;
+        v.copy_from(&Grid {
+            data: vec![7, v.as_view().get((0, 1)).copied().unwrap()],
+            width: 2,
+            height: 1,
+        });
+        assert_eq!(vec![0, 1, 2, 7, 9, 5], g.data);
+    }
+
+    #[test]
+    fn parse() {
+        let g: Grid<u8> = "012\n345\n".parse().unwrap();
+        assert_eq!((2, 3, &grid().data), (g.height, g.width, &g.data));
+
+        assert_eq!(
+            Err(ParseGridError::Empty),
+            "".parse::<Grid<u8>>().map(|g| g.data)
+        );
+        assert_eq!(
+            Err(ParseGridError::RaggedRow {
+                y: 2,
+                width: 2,
+                expected: 3
+            }),
+            "012\n345\n67".parse::<Grid<u8>>().map(|g| g.data)
+        );
+        assert_eq!(
+            Err(ParseGridError::InvalidChar { y: 1, x: 1, c: 'x' }),
+            "012\n3x5".parse::<Grid<u8>>().map(|g| g.data)
+        );
+    }
+
+    #[test]
+    fn parse_generic() {
+        #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+        enum Cell {
+            Wall,
+            Open,
+        }
+
+        impl TryFrom<char> for Cell {
+            type Error = ();
+
+            fn try_from(c: char) -> Result<Self, Self::Error> {
+                match c {
+                    '#' => Ok(Self::Wall),
+                    '.' | 'S' | 'E' => Ok(Self::Open),
+                    _ => Err(()),
+                }
             }
-            let v = self.get(pos);
-            write!(f, "{} ", *v)?;
         }
 
-        Ok(())
+        let g = Grid::<Cell>::from_chars("#.\n.#").unwrap();
+        assert_eq!(vec![Cell::Wall, Cell::Open, Cell::Open, Cell::Wall], g.data);
+        assert!(Grid::<Cell>::from_chars("#?").is_err());
+
+        let (g, [start, end, other]) =
+            Grid::parse_with_markers("#S\nE.".lines(), ['S', 'E', 'X'], |c| {
+                Cell::try_from(c).ok()
+            })
+            .unwrap();
+        assert_eq!(Some(CheckedYX::new(&g, (0, 1)).unwrap()), start);
+        assert_eq!(Some(CheckedYX::new(&g, (1, 0)).unwrap()), end);
+        assert_eq!(None, other);
+        assert_eq!(Cell::Open, *g.get(start.unwrap()));
     }
 }
@@ -0,0 +1,297 @@
+use std::{
+    fmt::Debug,
+    ops::{Add, Range, Sub},
+};
+
+/// An integer type that intervals can be made of.
+pub trait Int: Copy + Ord + Debug + Add<Output = Self> + Sub<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// Convert the integer to a `i128`, to compute lengths products without overflows.
+    ///
+    /// # Panics
+    /// If the value is a `u128` greater than `i128::MAX`.
+    fn as_i128(self) -> i128;
+}
+
+macro_rules! impl_int {
+    ($($t:ty),*) => {
+        $(impl Int for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            fn as_i128(self) -> i128 {
+                i128::try_from(self).expect("The integer does not fit in a i128")
+            }
+        })*
+    };
+}
+
+impl_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// The intersection of two ranges, or None if they do not intersect.
+pub fn intersect<T: Int>(a: &Range<T>, b: &Range<T>) -> Option<Range<T>> {
+    let range = a.start.max(b.start)..a.end.min(b.end);
+    (range.start < range.end).then_some(range)
+}
+
+/// A set of integers, stored as a sorted list of disjoint half-open intervals.
+///
+/// Intervals are normalized: they are never empty, and never overlap or touch each other.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Range<T>>,
+}
+
+impl<T: Int> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self { intervals: vec![] }
+    }
+
+    /// Remove every interval from the set, keeping its allocated memory.
+    pub fn clear(&mut self) {
+        self.intervals.clear()
+    }
+
+    /// Iterate over the intervals, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = Range<T>> + '_ {
+        self.intervals.iter().cloned()
+    }
+
+    /// Iterate over the intervals between the intervals of the set, in ascending order.
+    #[cfg(test)]
+    pub fn gaps(&self) -> impl Iterator<Item = Range<T>> + '_ {
+        self.intervals.windows(2).map(|w| w[0].end..w[1].start)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The total number of integers in the set.
+    pub fn len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::ZERO, |len, r| len + (r.end - r.start))
+    }
+
+    #[cfg(test)]
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.intervals.partition_point(|r| r.end <= value);
+        self.intervals.get(i).is_some_and(|r| r.start <= value)
+    }
+
+    /// Add a range of integers to the set, merging it with the intervals it overlaps or touches.
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.start >= range.end {
+            return;
+        }
+
+        let i = self.intervals.partition_point(|r| r.end < range.start);
+        let j = self.intervals.partition_point(|r| r.start <= range.end);
+
+        let merged = if i < j {
+            self.intervals[i].start.min(range.start)..self.intervals[j - 1].end.max(range.end)
+        } else {
+            range
+        };
+        self.intervals.splice(i..j, [merged]);
+    }
+
+    /// Add a single integer to the set.
+    #[cfg(test)]
+    pub fn insert_point(&mut self, value: T) {
+        self.insert(value..value + T::ONE)
+    }
+
+    /// Remove a range of integers from the set, trimming or splitting the intervals it overlaps.
+    pub fn remove(&mut self, range: Range<T>) {
+        if range.start >= range.end {
+            return;
+        }
+
+        let i = self.intervals.partition_point(|r| r.end <= range.start);
+        let j = self.intervals.partition_point(|r| r.start < range.end);
+        if i >= j {
+            return;
+        }
+
+        let left = self.intervals[i].start..range.start;
+        let right = range.end..self.intervals[j - 1].end;
+        let kept = [left, right].into_iter().filter(|r| r.start < r.end);
+        self.intervals.splice(i..j, kept);
+    }
+
+    /// Remove a single integer from the set.
+    pub fn remove_point(&mut self, value: T) {
+        self.remove(value..value + T::ONE)
+    }
+
+    /// The integers contained in either set.
+    #[cfg(test)]
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        let mut res = self.clone();
+        other.iter().for_each(|r| res.insert(r));
+        res
+    }
+
+    /// The integers contained in both sets.
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intervals = vec![];
+        let (mut a, mut b) = (
+            self.intervals.iter().peekable(),
+            other.intervals.iter().peekable(),
+        );
+
+        while let (Some(ra), Some(rb)) = (a.peek(), b.peek()) {
+            intervals.extend(intersect(ra, rb));
+
+            // Advance the interval that ends first, as it cannot intersect anything else
+            if ra.end < rb.end {
+                a.next();
+            } else {
+                b.next();
+            }
+        }
+
+        Self { intervals }
+    }
+
+    /// The integers contained in this set but not in the other.
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut res = self.clone();
+        other.iter().for_each(|r| res.remove(r));
+        res
+    }
+
+    /// The integers inside the bounds that are not contained in the set.
+    #[must_use]
+    pub fn complement(&self, bounds: Range<T>) -> Self {
+        Self::from(bounds).difference(self)
+    }
+}
+
+impl<T: Int> From<Range<T>> for IntervalSet<T> {
+    fn from(range: Range<T>) -> Self {
+        let mut set = Self::new();
+        set.insert(range);
+        set
+    }
+}
+
+impl<T: Int> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        iter.into_iter().for_each(|r| set.insert(r));
+        set
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::utils::test_rng::{Rng, SEED};
+
+    use super::{Int, IntervalSet};
+
+    /// Values of the brute-force model, all sets only contain values inside it
+    const N: i32 = 64;
+
+    fn model(set: &IntervalSet<i32>) -> Vec<bool> {
+        (0..N).map(|v| set.contains(v)).collect()
+    }
+
+    fn random_set(rng: &mut Rng) -> (IntervalSet<i32>, Vec<bool>) {
+        let mut set = IntervalSet::new();
+        let mut expected = vec![false; N as usize];
+        for _ in 0..rng.range(0..8) {
+            let range = rng.interval(0..N, 0..12);
+            let range = range.start..range.end.min(N);
+            if rng.range(0..3) == 0 {
+                set.remove(range.clone());
+                range.for_each(|v| expected[v as usize] = false);
+            } else {
+                set.insert(range.clone());
+                range.for_each(|v| expected[v as usize] = true);
+            }
+        }
+        (set, expected)
+    }
+
+    /// Check that the intervals are sorted, non-empty and do not touch each other
+    fn assert_normalized(set: &IntervalSet<i32>) {
+        assert!(set.iter().all(|r| r.start < r.end), "{set:?}");
+        assert!(set.gaps().all(|r| r.start < r.end), "{set:?}");
+    }
+
+    #[test]
+    fn insert_remove() {
+        let mut set = IntervalSet::from(0..5);
+        set.insert(8..10);
+        set.insert(5..6);
+        assert_eq!(vec![0..6, 8..10], set.iter().collect::<Vec<_>>());
+        assert_eq!(vec![6..8], set.gaps().collect::<Vec<_>>());
+
+        set.remove_point(3);
+        set.insert_point(7);
+        assert_eq!(vec![0..3, 4..6, 7..10], set.iter().collect::<Vec<_>>());
+        assert_eq!(8, set.len());
+        assert!(set.contains(4) && !set.contains(3) && !set.contains(10));
+
+        set.remove(2..9);
+        assert_eq!(vec![0..2, 9..10], set.iter().collect::<Vec<_>>());
+        assert_eq!(
+            vec![-2..0, 2..9, 10..12],
+            set.complement(-2..12).iter().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn as_i128() {
+        assert_eq!(-5, (-5i8).as_i128());
+        assert_eq!(i128::MAX, (i128::MAX as u128).as_i128());
+    }
+
+    #[test]
+    #[should_panic]
+    fn as_i128_too_large() {
+        u128::MAX.as_i128();
+    }
+
+    #[test]
+    fn random_insert_remove() {
+        let mut rng = Rng::new(SEED);
+        for _ in 0..1000 {
+            let (set, expected) = random_set(&mut rng);
+
+            assert_normalized(&set);
+            assert_eq!(expected, model(&set));
+            assert_eq!(expected.iter().filter(|&&b| b).count() as i32, set.len());
+        }
+    }
+
+    #[test]
+    fn random_set_operations() {
+        let mut rng = Rng::new(SEED);
+        for _ in 0..1000 {
+            let (a, ma) = random_set(&mut rng);
+            let (b, mb) = random_set(&mut rng);
+            let zip = |f: fn(bool, bool) -> bool| -> Vec<bool> {
+                ma.iter().zip(&mb).map(|(&a, &b)| f(a, b)).collect()
+            };
+
+            for (set, expected) in [
+                (a.union(&b), zip(|a, b| a || b)),
+                (a.intersection(&b), zip(|a, b| a && b)),
+                (a.difference(&b), zip(|a, b| a && !b)),
+                (a.complement(0..N), zip(|a, _| !a)),
+            ] {
+                assert_normalized(&set);
+                assert_eq!(expected, model(&set));
+            }
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use std::ops::{Div, Mul, Rem};
+
+use super::intervals::Int;
+
+/// Greatest common divisor of two non-negative integers. `gcd(0, 0)` is 0.
+pub fn gcd<T: Int + Rem<Output = T>>(mut a: T, mut b: T) -> T {
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple of two non-negative integers. It is 0 if one of them is.
+pub fn lcm<T>(a: T, b: T) -> T
+where
+    T: Int + Rem<Output = T> + Div<Output = T> + Mul<Output = T>,
+{
+    if a == T::ZERO || b == T::ZERO {
+        T::ZERO
+    } else {
+        a / gcd(a, b) * b
+    }
+}
+
+/// Least common multiple of all the integers, or 1 if there are none.
+pub fn lcm_all<T>(values: impl IntoIterator<Item = T>) -> T
+where
+    T: Int + Rem<Output = T> + Div<Output = T> + Mul<Output = T>,
+{
+    values.into_iter().fold(T::ONE, lcm)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::utils::test_rng::{Rng, SEED};
+
+    use super::{gcd, lcm, lcm_all};
+
+    #[test]
+    fn gcd_lcm() {
+        assert_eq!(6, gcd(12u32, 18));
+        assert_eq!(36, lcm(12u32, 18));
+        assert_eq!(5, gcd(0u8, 5));
+        assert_eq!(0, lcm(0u8, 5));
+        assert_eq!(1, lcm_all(Vec::<u64>::new()));
+        assert_eq!(96577, lcm_all([23u64, 19, 13, 17]));
+        assert_eq!(60, lcm_all([4u64, 6, 10, 15]));
+
+        let mut rng = Rng::new(SEED);
+        for _ in 0..1000 {
+            let (a, b) = (rng.range(1..1_000_000), rng.range(1..1_000_000));
+            let g = gcd(a, b);
+            assert_eq!((0, 0), (a % g, b % g));
+            assert_eq!(1, gcd(a / g, b / g));
+            assert_eq!(a * b, g * lcm(a, b));
+        }
+    }
+}
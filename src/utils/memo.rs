@@ -0,0 +1,294 @@
+//! Memoization of recursive functions, for dynamic programming.
+
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    fmt::Display,
+    hash::Hash,
+    marker::PhantomData,
+    ops::{Add, AddAssign},
+};
+
+/// A cache of computed values.
+pub trait Store<K, V> {
+    fn get(&mut self, key: &K) -> Option<&V>;
+
+    fn insert(&mut self, key: K, value: V);
+
+    /// The number of cached values.
+    #[cfg(test)]
+    fn len(&self) -> usize;
+}
+
+impl<K: Hash + Eq, V> Store<K, V> for HashMap<K, V> {
+    fn get(&mut self, key: &K) -> Option<&V> {
+        HashMap::get(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        HashMap::insert(self, key, value);
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+}
+
+/// A cache for keys mapped to small indices, storing the values in a vector.
+pub struct Dense<K, V> {
+    values: Vec<Option<V>>,
+    index: fn(&K) -> usize,
+}
+
+impl<K, V> Dense<K, V> {
+    /// Create an empty cache, `index` mapping each key to its own index.
+    pub fn new(index: fn(&K) -> usize) -> Self {
+        Self {
+            values: Vec::new(),
+            index,
+        }
+    }
+}
+
+impl<K, V> Store<K, V> for Dense<K, V> {
+    fn get(&mut self, key: &K) -> Option<&V> {
+        self.values.get((self.index)(key))?.as_ref()
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        let i = (self.index)(&key);
+        if self.values.len() <= i {
+            self.values.resize_with(i + 1, || None);
+        }
+        self.values[i] = Some(value);
+    }
+
+    #[cfg(test)]
+    fn len(&self) -> usize {
+        self.values.iter().filter(|v| v.is_some()).count()
+    }
+}
+
+/// Counters of the cache lookups of a memoized function.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Lookups whose value was already cached
+    pub hits: u64,
+    /// Lookups whose value had to be computed
+    pub misses: u64,
+}
+
+impl Stats {
+    pub fn lookups(&self) -> u64 {
+        self.hits + self.misses
+    }
+
+    /// The ratio of hits over all lookups, or 0 if there were none.
+    pub fn hit_rate(&self) -> f64 {
+        if self.lookups() == 0 {
+            0.
+        } else {
+            self.hits as f64 / self.lookups() as f64
+        }
+    }
+}
+
+impl Add for Stats {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            hits: self.hits + rhs.hits,
+            misses: self.misses + rhs.misses,
+        }
+    }
+}
+
+impl AddAssign for Stats {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} hits, {} misses ({:.1}% hit rate)",
+            self.hits,
+            self.misses,
+            100. * self.hit_rate()
+        )
+    }
+}
+
+thread_local! {
+    /// The stats of the memos dropped on this thread, since they were last taken.
+    static RECORDED: Cell<Option<Stats>> = const { Cell::new(None) };
+}
+
+/// Take the summed stats of the memos used and dropped on this thread since the last call,
+/// or None if there were none.
+///
+/// Memos used from other threads (e.g. by rayon) record their stats on those threads.
+pub fn take_stats() -> Option<Stats> {
+    RECORDED.with(Cell::take)
+}
+
+/// A memoized recursive function from `K` to `V`, caching its values in a [`Store`].
+///
+/// The function itself is given to [`Memo::get`], and receives a callback to recurse with.
+/// When dropped, the memo records its [`Stats`] so that they can be reported with [`take_stats`].
+pub struct Memo<K, V, S = HashMap<K, V>> {
+    store: S,
+    stats: Stats,
+    _marker: PhantomData<fn(K) -> V>,
+}
+
+impl<K: Hash + Eq, V> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::with_store(HashMap::new())
+    }
+}
+
+impl<K, V> Memo<K, V, Dense<K, V>> {
+    /// A memo storing its values in a vector, `index` mapping each key to its own index.
+    pub fn dense(index: fn(&K) -> usize) -> Self {
+        Self::with_store(Dense::new(index))
+    }
+}
+
+impl<K, V, S> Memo<K, V, S> {
+    pub fn with_store(store: S) -> Self {
+        Self {
+            store,
+            stats: Stats::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    #[cfg(test)]
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+}
+
+impl<K, V: Clone, S: Store<K, V>> Memo<K, V, S> {
+    /// The value of `f` for the key, computed only if it is not already cached.
+    ///
+    /// `f(recurse, key)` computes the value of a key, calling `recurse(other_key)`
+    /// to get the (memoized) values it depends on.
+    pub fn get<F>(&mut self, key: K, f: &F) -> V
+    where
+        F: Fn(&mut dyn FnMut(K) -> V, &K) -> V,
+    {
+        if let Some(value) = self.store.get(&key) {
+            self.stats.hits += 1;
+            return value.clone();
+        }
+
+        self.stats.misses += 1;
+        let value = f(&mut |k| self.get(k, f), &key);
+        self.store.insert(key, value.clone());
+        value
+    }
+}
+
+impl<K: Hash + Eq, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, S> Drop for Memo<K, V, S> {
+    fn drop(&mut self) {
+        if self.stats.lookups() > 0 {
+            RECORDED.with(|recorded| {
+                recorded.set(Some(recorded.get().unwrap_or_default() + self.stats));
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{take_stats, Memo, Stats, Store};
+
+    fn fibonacci(recurse: &mut dyn FnMut(u64) -> u64, &n: &u64) -> u64 {
+        if n < 2 {
+            n
+        } else {
+            recurse(n - 1) + recurse(n - 2)
+        }
+    }
+
+    #[test]
+    fn backends() {
+        let mut memo = Memo::new();
+        assert_eq!(12586269025, memo.get(50, &fibonacci));
+        // Each value is computed once, and fib(n - 2) is then found in the cache
+        assert_eq!(
+            Stats {
+                hits: 48,
+                misses: 51
+            },
+            memo.stats()
+        );
+        assert_eq!(12586269025, memo.get(50, &fibonacci));
+        assert_eq!(49, memo.stats().hits);
+
+        let mut dense = Memo::dense(|&n| n as usize);
+        assert_eq!(12586269025, dense.get(50, &fibonacci));
+        assert_eq!(51, dense.store().len());
+    }
+
+    #[test]
+    fn captures() {
+        // Number of paths to the bottom right corner of a grid, avoiding the walls
+        let walls = [(1, 1), (2, 3)];
+        let paths = |recurse: &mut dyn FnMut((usize, usize)) -> u64, &(y, x): &(usize, usize)| {
+            if walls.contains(&(y, x)) {
+                0
+            } else if (y, x) == (4, 4) {
+                1
+            } else {
+                let down = if y < 4 { recurse((y + 1, x)) } else { 0 };
+                let right = if x < 4 { recurse((y, x + 1)) } else { 0 };
+                down + right
+            }
+        };
+
+        let mut memo = Memo::new();
+        let expected = memo.get((0, 0), &paths);
+        let mut dense = Memo::dense(|&(y, x)| y * 5 + x);
+        assert_eq!(expected, dense.get((0, 0), &paths));
+        assert_eq!(18, expected);
+    }
+
+    #[test]
+    fn recorded_stats() {
+        take_stats();
+        Memo::new().get(10, &fibonacci);
+        Memo::new().get(5, &fibonacci);
+        Memo::<u64, u64>::new();
+
+        assert_eq!(
+            Some(Stats {
+                hits: 8 + 3,
+                misses: 11 + 6
+            }),
+            take_stats()
+        );
+        assert_eq!(None, take_stats());
+        assert_eq!(
+            "3 hits, 1 misses (75.0% hit rate)",
+            Stats { hits: 3, misses: 1 }.to_string()
+        );
+    }
+}
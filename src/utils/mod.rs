@@ -1,13 +1,26 @@
-use std::{
-    mem::MaybeUninit,
-    ops::{BitOr, Shl},
-};
+use std::mem::MaybeUninit;
 
 use itertools::{EitherOrBoth, Itertools};
 
+pub mod bits;
+pub mod bitset;
+pub mod boxes;
+pub mod cycle;
+pub mod flood;
+pub mod geom;
 mod grid;
-
-pub use grid::{CheckedYX, Grid, YX};
+pub mod intervals;
+pub mod math;
+pub mod memo;
+pub mod ocr;
+pub mod parse;
+pub mod registration;
+pub mod render;
+pub mod search;
+#[cfg(test)]
+pub mod test_rng;
+
+pub use grid::{CheckedYX, Grid, ParseGridError, YX};
 
 /// Build the URL to get an AOC input
 pub fn get_input_url(year: u16, day: u8) -> String {
@@ -48,32 +61,3 @@ pub trait TryCollectArray {
 }
 
 impl<I: Iterator + Sized> TryCollectArray for I {}
-
-/// Collect a number of bits into a integer.
-/// The last element in the iterator will be the LSB.
-/// Previous element will constitute the more significant bits in order.
-///
-/// If the iterator does not contain the number of bits required, return None.
-pub fn collect_n_bits<T>(bits: &mut dyn Iterator<Item = bool>, n: u8) -> Option<T>
-where
-    bool: Into<T>,
-    T: Shl<u8, Output = T> + BitOr<T, Output = T> + Default,
-{
-    let mut r = T::default();
-    for _ in 0..n {
-        let b = bits.next()?;
-        r = (r << 1) | b.into();
-    }
-    Some(r)
-}
-
-#[macro_export]
-macro_rules! unwrap_or_continue {
-    ($opt:expr) => {
-        if let Some(r) = $opt {
-            r
-        } else {
-            continue;
-        }
-    };
-}
@@ -0,0 +1,209 @@
+//! Read the letters drawn by some puzzles, in the font used by AoC.
+
+use super::Grid;
+
+/// A font of letters with the same height, drawn with `#` for lit pixels.
+struct Font {
+    height: usize,
+    glyphs: &'static [(char, &'static str)],
+}
+
+/// The 4x6 font, used by most puzzles drawing letters.
+const SMALL: Font = Font {
+    height: 6,
+    glyphs: &[
+        ('A', ".##.\n#..#\n#..#\n####\n#..#\n#..#"),
+        ('B', "###.\n#..#\n###.\n#..#\n#..#\n###."),
+        ('C', ".##.\n#..#\n#...\n#...\n#..#\n.##."),
+        ('E', "####\n#...\n###.\n#...\n#...\n####"),
+        ('F', "####\n#...\n###.\n#...\n#...\n#..."),
+        ('G', ".##.\n#..#\n#...\n#.##\n#..#\n.###"),
+        ('H', "#..#\n#..#\n####\n#..#\n#..#\n#..#"),
+        ('I', "###\n.#.\n.#.\n.#.\n.#.\n###"),
+        ('J', "..##\n...#\n...#\n...#\n#..#\n.##."),
+        ('K', "#..#\n#.#.\n##..\n#.#.\n#.#.\n#..#"),
+        ('L', "#...\n#...\n#...\n#...\n#...\n####"),
+        ('O', ".##.\n#..#\n#..#\n#..#\n#..#\n.##."),
+        ('P', "###.\n#..#\n#..#\n###.\n#...\n#..."),
+        ('R', "###.\n#..#\n#..#\n###.\n#.#.\n#..#"),
+        ('S', ".###\n#...\n#...\n.##.\n...#\n###."),
+        ('U', "#..#\n#..#\n#..#\n#..#\n#..#\n.##."),
+        ('Y', "#...#\n#...#\n.#.#.\n..#..\n..#..\n..#.."),
+        ('Z', "####\n...#\n..#.\n.#..\n#...\n####"),
+    ],
+};
+
+/// The 6x10 font, used by the puzzles drawing bigger letters.
+#[rustfmt::skip]
+const LARGE: Font = Font {
+    height: 10,
+    glyphs: &[
+        ('A', "..##..\n.#..#.\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#"),
+        ('B', "#####.\n#....#\n#....#\n#....#\n#####.\n#....#\n#....#\n#....#\n#....#\n#####."),
+        ('C', ".####.\n#....#\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#....#\n.####."),
+        ('E', "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n######"),
+        ('F', "######\n#.....\n#.....\n#.....\n#####.\n#.....\n#.....\n#.....\n#.....\n#....."),
+        ('G', ".####.\n#....#\n#.....\n#.....\n#.....\n#..###\n#....#\n#....#\n#...##\n.###.#"),
+        ('H', "#....#\n#....#\n#....#\n#....#\n######\n#....#\n#....#\n#....#\n#....#\n#....#"),
+        ('J', "...###\n....#.\n....#.\n....#.\n....#.\n....#.\n....#.\n#...#.\n#...#.\n.###.."),
+        ('K', "#....#\n#...#.\n#..#..\n#.#...\n##....\n##....\n#.#...\n#..#..\n#...#.\n#....#"),
+        ('L', "#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n#.....\n######"),
+        ('N', "#....#\n##...#\n##...#\n#.#..#\n#.#..#\n#..#.#\n#..#.#\n#...##\n#...##\n#....#"),
+        ('P', "#####.\n#....#\n#....#\n#....#\n#####.\n#.....\n#.....\n#.....\n#.....\n#....."),
+        ('R', "#####.\n#....#\n#....#\n#....#\n#####.\n#..#..\n#...#.\n#...#.\n#....#\n#....#"),
+        ('X', "#....#\n#....#\n.#..#.\n.#..#.\n..##..\n..##..\n.#..#.\n.#..#.\n#....#\n#....#"),
+        ('Z', "######\n.....#\n.....#\n....#.\n...#..\n..#...\n.#....\n#.....\n#.....\n######"),
+    ],
+};
+
+impl Font {
+    /// The letter drawn by the pixels of a glyph, row by row.
+    fn letter(&self, pixels: &[Vec<bool>]) -> Option<char> {
+        self.glyphs
+            .iter()
+            .find(|(_, art)| {
+                art.lines()
+                    .zip(pixels)
+                    .all(|(art, row)| art.chars().map(|c| c == '#').eq(row.iter().copied()))
+            })
+            .map(|&(c, _)| c)
+    }
+
+    /// The pixels of a letter glyph, or None if it is not part of the font.
+    #[cfg(test)]
+    fn glyph(&self, letter: char) -> Option<Grid<bool>> {
+        let (_, art) = self.glyphs.iter().find(|(c, _)| *c == letter)?;
+        Grid::from_str_map(art.lines(), |c| c == '#').ok()
+    }
+}
+
+/// Read the letters drawn on an image, or None if some shape is not a known letter.
+///
+/// The image may have blank borders, and any number of blank columns between the letters.
+pub fn recognize(image: impl AsRef<Grid<bool>>) -> Option<String> {
+    let image = image.as_ref();
+
+    // Only keep the rows with lit pixels, which must be as high as the letters of a font
+    let lit_rows = (0..image.height)
+        .filter(|&y| image.row(y).contains(&true))
+        .collect::<Vec<_>>();
+    let (top, bottom) = (*lit_rows.first()?, *lit_rows.last()?);
+    let font = [SMALL, LARGE]
+        .into_iter()
+        .find(|font| font.height == bottom - top + 1)?;
+
+    // Split the letters on blank columns
+    let is_blank = |x: usize| image.column(x).all(|&b| !b);
+    let mut letters = String::new();
+    let mut x = 0;
+    while x < image.width {
+        if is_blank(x) {
+            x += 1;
+            continue;
+        }
+
+        let start = x;
+        while x < image.width && !is_blank(x) {
+            x += 1;
+        }
+
+        let pixels = (top..=bottom)
+            .map(|y| image.row(y)[start..x].to_vec())
+            .collect::<Vec<_>>();
+        letters.push(font.letter(&pixels)?);
+    }
+
+    Some(letters)
+}
+
+/// Draw text in the 4x6 font, with one blank column between the letters.
+/// Return None if a character is not part of the font.
+#[cfg(test)]
+pub fn draw(text: &str) -> Option<Grid<bool>> {
+    let glyphs = text
+        .chars()
+        .map(|c| SMALL.glyph(c))
+        .collect::<Option<Vec<_>>>()?;
+
+    let width = glyphs.iter().map(|g| g.width + 1).sum::<usize>().max(1) - 1;
+    let mut image = Grid::new(SMALL.height, width, false);
+    let mut x0 = 0;
+    for glyph in glyphs {
+        for (y, row) in glyph.rows().enumerate() {
+            image.row_mut(y)[x0..x0 + glyph.width].copy_from_slice(row);
+        }
+        x0 += glyph.width + 1;
+    }
+
+    Some(image)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::utils::Grid;
+
+    use super::{draw, recognize, LARGE, SMALL};
+
+    fn parse_art(art: &str) -> Grid<bool> {
+        Grid::from_str_map(art.lines(), |c| c == '#').unwrap()
+    }
+
+    #[test]
+    fn every_glyph() {
+        for font in [SMALL, LARGE] {
+            for &(c, art) in font.glyphs {
+                let glyph = parse_art(art);
+                assert_eq!(font.height, glyph.height, "glyph {c}");
+                assert_eq!(Some(c.to_string()), recognize(&glyph), "glyph {c}");
+            }
+        }
+
+        let alphabet = SMALL.glyphs.iter().map(|(c, _)| c).collect::<String>();
+        assert_eq!(Some(alphabet.clone()), recognize(draw(&alphabet).unwrap()));
+    }
+
+    #[test]
+    fn letters_in_image() {
+        // Letters in 5 columns cells, with a blank border
+        let image = parse_art(
+            "......................
+.#..#.####.#....#.....
+.#..#.#....#....#.....
+.####.###..#....#.....
+.#..#.#....#....#.....
+.#..#.#....#....#.....
+.#..#.####.####.####..
+......................",
+        );
+        assert_eq!(Some("HELL".to_owned()), recognize(&image));
+
+        let large = parse_art(
+            "#....#..#####.
+#....#..#....#
+.#..#...#....#
+.#..#...#....#
+..##....#####.
+..##....#.....
+.#..#...#.....
+.#..#...#.....
+#....#..#.....
+#....#..#.....",
+        );
+        assert_eq!(Some("XP".to_owned()), recognize(&large));
+    }
+
+    #[test]
+    fn unknown() {
+        assert!(draw("AB?").is_none());
+        assert_eq!(None, recognize(Grid::new(6, 10, false)));
+
+        // Not a letter
+        let mut image = draw("AB").unwrap();
+        *image.row_mut(0).last_mut().unwrap() = true;
+        assert_eq!(None, recognize(&image));
+
+        // Not the height of a font
+        let image = parse_art("##\n#.\n##");
+        assert_eq!(None, recognize(&image));
+    }
+}
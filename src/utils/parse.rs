@@ -0,0 +1,435 @@
+use std::{any::type_name, fmt::Display, str::FromStr};
+
+/// An error while parsing the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// A value could not be converted into the expected type
+    InvalidValue { value: String, ty: &'static str },
+    /// The text does not have the expected form
+    Mismatch { expected: String, found: String },
+    /// A key is missing from key-value lines
+    MissingKey(String),
+    /// An error on a line of the input. Lines start at 1
+    AtLine { line: usize, error: Box<ParseError> },
+}
+
+impl ParseError {
+    /// Attach the line the error happened on.
+    /// If the error already has a line, it is considered relative to this one.
+    pub fn at_line(self, line: usize) -> Self {
+        match self {
+            Self::AtLine { line: inner, error } => Self::AtLine {
+                line: line + inner - 1,
+                error,
+            },
+            error => Self::AtLine {
+                line,
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// The line the error happened on, if known.
+    #[cfg(test)]
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Self::AtLine { line, .. } => Some(*line),
+            _ => None,
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidValue { value, ty } => write!(f, "could not parse {value:?} as {ty}"),
+            Self::Mismatch { expected, found } => {
+                write!(f, "expected {expected:?}, found {found:?}")
+            }
+            Self::MissingKey(key) => write!(f, "missing key {key:?}"),
+            Self::AtLine { line, error } => write!(f, "line {line}: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parse a value, ignoring the surrounding whitespace.
+pub fn value<T: FromStr>(s: &str) -> Result<T, ParseError> {
+    let s = s.trim();
+    s.parse().map_err(|_| ParseError::InvalidValue {
+        value: s.to_owned(),
+        ty: type_name::<T>(),
+    })
+}
+
+/// Parse all the integers in some text, ignoring everything else.
+///
+/// A `-` is a sign when directly before the digits and not after a letter or digit,
+/// so `x=-2` contains `-2` but `2-4` contains `2` and `4`.
+#[cfg(test)]
+pub fn ints<T: FromStr>(s: &str) -> Result<Vec<T>, ParseError> {
+    let bytes = s.as_bytes();
+    let mut ints = vec![];
+
+    let mut i = 0;
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+
+        let is_sign =
+            |j: usize| bytes[j] == b'-' && (j == 0 || !bytes[j - 1].is_ascii_alphanumeric());
+        let start = if i > 0 && is_sign(i - 1) { i - 1 } else { i };
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        ints.push(value(&s[start..i])?);
+    }
+
+    Ok(ints)
+}
+
+/// Parse a list of values separated by `sep`, e.g. `3,4,3,1,2`.
+pub fn list<T: FromStr>(s: &str, sep: &str) -> Result<Vec<T>, ParseError> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Ok(vec![]);
+    }
+    s.split(sep).map(value).collect()
+}
+
+/// Parse each line with `f`, tagging errors with their line.
+/// Lines are trimmed, and blank lines are skipped.
+pub fn lines<T>(
+    s: &str,
+    mut f: impl FnMut(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    s.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(n, line)| f(line).map_err(|e| e.at_line(n)))
+        .collect()
+}
+
+/// Parse each paragraph (group of lines separated by blank lines) with `f`,
+/// tagging errors with their line, relative to the start of the paragraph.
+pub fn paragraphs<T>(
+    s: &str,
+    mut f: impl FnMut(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    let mut results = vec![];
+
+    // The first line and byte offsets of the current paragraph
+    let mut current: Option<(usize, usize, usize)> = None;
+    let mut offset = 0;
+    for (i, line) in s.split('\n').enumerate() {
+        let end = offset + line.len();
+        if line.trim().is_empty() {
+            if let Some((n, start, end)) = current.take() {
+                results.push(f(&s[start..end]).map_err(|e| e.at_line(n))?);
+            }
+        } else {
+            let (_, _, par_end) = current.get_or_insert((i + 1, offset, end));
+            *par_end = end;
+        }
+        offset = end + 1;
+    }
+    if let Some((n, start, end)) = current {
+        results.push(f(&s[start..end]).map_err(|e| e.at_line(n))?);
+    }
+
+    Ok(results)
+}
+
+/// Lines of `key<sep>value`, e.g. `Test: divisible by 23`.
+pub struct KeyValues<'a> {
+    /// The line, key and value of each entry
+    entries: Vec<(usize, &'a str, &'a str)>,
+}
+
+impl<'a> KeyValues<'a> {
+    /// Split each line on the first separator. Keys and values are trimmed, blank lines skipped.
+    pub fn parse(s: &'a str, sep: &str) -> Result<Self, ParseError> {
+        let mut entries = vec![];
+        for (i, line) in s.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let (key, value) = line.split_once(sep).ok_or_else(|| {
+                ParseError::Mismatch {
+                    expected: format!("key{sep}value"),
+                    found: line.trim().to_owned(),
+                }
+                .at_line(i + 1)
+            })?;
+            entries.push((i + 1, key.trim(), value.trim()));
+        }
+
+        Ok(Self { entries })
+    }
+
+    /// The line and value of the first entry with the key.
+    fn entry(&self, key: &str) -> Result<(usize, &'a str), ParseError> {
+        self.entries
+            .iter()
+            .find(|(_, k, _)| *k == key)
+            .map(|&(n, _, v)| (n, v))
+            .ok_or_else(|| ParseError::MissingKey(key.to_owned()))
+    }
+
+    /// The value of the key, as written.
+    pub fn raw(&self, key: &str) -> Result<&'a str, ParseError> {
+        self.entry(key).map(|(_, v)| v)
+    }
+
+    /// Parse the value of the key, or the value after a prefix
+    /// (e.g. `divisible by ` for `Test: divisible by 23`).
+    pub fn get<T: FromStr>(&self, key: &str, prefix: &str) -> Result<T, ParseError> {
+        let (n, v) = self.entry(key)?;
+        let v = v.strip_prefix(prefix).ok_or_else(|| {
+            ParseError::Mismatch {
+                expected: prefix.to_owned(),
+                found: v.to_owned(),
+            }
+            .at_line(n)
+        })?;
+        value(v).map_err(|e| e.at_line(n))
+    }
+}
+
+/// A line template, made of literal text and `{}` placeholders.
+/// Placeholders may contain a label for readability (e.g. `{x}`), which is ignored.
+///
+/// Each placeholder captures the text up to the next occurrence of the following literal text.
+pub struct Pattern<'p> {
+    /// The literal text around the placeholders, one more than the placeholders
+    literals: Vec<&'p str>,
+}
+
+impl<'p> Pattern<'p> {
+    /// # Panics
+    /// If a placeholder is not closed, or is right after another one.
+    pub fn new(pattern: &'p str) -> Self {
+        let mut literals = vec![];
+        let mut rest = pattern;
+        while let Some(start) = rest.find('{') {
+            let len = rest[start..].find('}').expect("unclosed placeholder");
+            literals.push(&rest[..start]);
+            rest = &rest[start + len + 1..];
+        }
+        literals.push(rest);
+
+        assert!(
+            literals.iter().skip(1).rev().skip(1).all(|l| !l.is_empty()),
+            "ambiguous pattern {pattern:?}: placeholders must be separated by text"
+        );
+        Self { literals }
+    }
+
+    /// The text captured by each placeholder.
+    pub fn captures<'s>(&self, s: &'s str) -> Result<Vec<&'s str>, ParseError> {
+        let mismatch = |expected: &str, found: &str| ParseError::Mismatch {
+            expected: expected.to_owned(),
+            found: found.to_owned(),
+        };
+
+        let (first, others) = self.literals.split_first().unwrap();
+        let mut rest = s.strip_prefix(first).ok_or_else(|| mismatch(first, s))?;
+        if others.is_empty() && !rest.is_empty() {
+            return Err(mismatch("", rest));
+        }
+
+        let mut captures = Vec::with_capacity(others.len());
+        for (i, literal) in others.iter().enumerate() {
+            let (capture, after) = if i == others.len() - 1 {
+                // The last literal must end the text
+                let capture = rest
+                    .strip_suffix(literal)
+                    .ok_or_else(|| mismatch(literal, rest))?;
+                (capture, "")
+            } else {
+                rest.split_once(literal)
+                    .ok_or_else(|| mismatch(literal, rest))?
+            };
+            captures.push(capture);
+            rest = after;
+        }
+
+        Ok(captures)
+    }
+
+    /// Parse each capture into the corresponding element of a tuple or array.
+    pub fn parse<T: FromCaptures>(&self, s: &str) -> Result<T, ParseError> {
+        T::from_captures(&self.captures(s)?)
+    }
+}
+
+/// Parse a line with a pattern (see [`Pattern`]).
+///
+/// ```ignore
+/// let (x, y): (i32, i32) = parse::pattern("at x={x}, y={y}", "at x=2, y=-3")?;
+/// ```
+pub fn pattern<T: FromCaptures>(pattern: &str, s: &str) -> Result<T, ParseError> {
+    Pattern::new(pattern).parse(s)
+}
+
+/// A type that can be built from the captures of a [`Pattern`].
+pub trait FromCaptures: Sized {
+    /// # Panics
+    /// If there are not as many captures as expected, as the pattern does not match the type.
+    fn from_captures(captures: &[&str]) -> Result<Self, ParseError>;
+}
+
+macro_rules! impl_from_captures {
+    ($n:literal: $($t:ident),+) => {
+        impl<$($t: FromStr),+> FromCaptures for ($($t,)+) {
+            fn from_captures(captures: &[&str]) -> Result<Self, ParseError> {
+                assert_eq!($n, captures.len(), "the pattern should have {} placeholders", $n);
+                let mut captures = captures.iter();
+                Ok(($(value::<$t>(captures.next().unwrap())?,)+))
+            }
+        }
+    };
+}
+
+impl_from_captures!(1: A);
+impl_from_captures!(2: A, B);
+impl_from_captures!(3: A, B, C);
+impl_from_captures!(4: A, B, C, D);
+impl_from_captures!(5: A, B, C, D, E);
+impl_from_captures!(6: A, B, C, D, E, F);
+impl_from_captures!(7: A, B, C, D, E, F, G);
+impl_from_captures!(8: A, B, C, D, E, F, G, H);
+
+impl<T: FromStr, const N: usize> FromCaptures for [T; N] {
+    fn from_captures(captures: &[&str]) -> Result<Self, ParseError> {
+        assert_eq!(
+            N,
+            captures.len(),
+            "the pattern should have {N} placeholders"
+        );
+        let values = captures
+            .iter()
+            .map(|c| value(c))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(values.try_into().ok().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ints, list, paragraphs, pattern, value, KeyValues, ParseError, Pattern};
+
+    #[test]
+    fn values() {
+        assert_eq!(Ok(-12), value::<i32>(" -12 "));
+        assert_eq!(
+            Err(ParseError::InvalidValue {
+                value: "-12".to_owned(),
+                ty: "u32"
+            }),
+            value::<u32>("-12")
+        );
+
+        assert_eq!(Ok(vec![3, 4, 3, 1, 2]), list::<u8>("3,4,3,1,2\n", ","));
+        assert_eq!(Ok(vec![]), list::<u8>("", ","));
+        assert!(list::<u8>("1, 2,,3", ",").is_err());
+
+        assert_eq!(
+            Ok(vec![2, 18, -2, 15]),
+            ints::<i32>("Sensor at x=2, y=18: closest beacon is at x=-2, y=15")
+        );
+        assert_eq!(Ok(vec![2, 4, 6, 8]), ints::<u8>("2-4,6-8"));
+        assert_eq!(Ok(vec![-1, 3]), ints::<i64>("-1 -> a3"));
+        assert!(ints::<u8>("x=300").is_err());
+    }
+
+    #[test]
+    fn line_numbers() {
+        let data = "1\n2\n\nx\n";
+        let err = super::lines(data, value::<u8>).unwrap_err();
+        assert_eq!(Some(4), err.line());
+        assert_eq!("line 4: could not parse \"x\" as u8", err.to_string());
+
+        let data = "1\n2\n\n\n3\n4\nx\n\n5";
+        let sums = paragraphs("1\n2\n\n\n3\n4\n\n5\n", |p| {
+            super::lines(p, value::<u32>).map(|v| v.iter().sum::<u32>())
+        });
+        assert_eq!(Ok(vec![3, 7, 5]), sums);
+
+        // Errors in a paragraph are reported at their line in the whole text
+        let err = paragraphs(data, |p| super::lines(p, value::<u32>)).unwrap_err();
+        assert_eq!(Some(7), err.line());
+    }
+
+    #[test]
+    fn key_values() {
+        let data = "Monkey 0:
+  Starting items: 79, 98
+  Test: divisible by 23
+  If true: throw to monkey x";
+        let kv = KeyValues::parse(data, ":").unwrap();
+
+        assert_eq!(Ok("79, 98"), kv.raw("Starting items"));
+        assert_eq!(Ok(23), kv.get::<u32>("Test", "divisible by "));
+        assert_eq!(
+            Some(4),
+            kv.get::<u8>("If true", "throw to monkey ")
+                .unwrap_err()
+                .line()
+        );
+        assert_eq!(
+            Err(ParseError::MissingKey("If false".to_owned())),
+            kv.get::<u8>("If false", "throw to monkey ")
+        );
+        assert_eq!(
+            Some(3),
+            KeyValues::parse("a: 1\n\nb 2", ":")
+                .err()
+                .and_then(|e| e.line())
+        );
+    }
+
+    #[test]
+    fn patterns() {
+        let sensor = Pattern::new("Sensor at x={x}, y={y}: closest beacon is at x={}, y={}");
+        assert_eq!(
+            Ok((2, 18, -2, 15)),
+            sensor.parse::<(i32, i32, i64, i8)>(
+                "Sensor at x=2, y=18: closest beacon is at x=-2, y=15"
+            )
+        );
+        assert_eq!(
+            Ok([2, 18, -2, 15]),
+            sensor.parse::<[i32; 4]>("Sensor at x=2, y=18: closest beacon is at x=-2, y=15")
+        );
+        assert!(matches!(
+            sensor.parse::<[i32; 4]>("Sensor at x=2, y=18: closest beacon is at y=-2, y=15"),
+            Err(ParseError::Mismatch { .. })
+        ));
+        assert!(matches!(
+            sensor.parse::<[i32; 4]>("Sensor at x=2, y=18: closest beacon is at x=a, y=15"),
+            Err(ParseError::InvalidValue { .. })
+        ));
+
+        // Placeholders at the start and the end capture everything
+        assert_eq!(
+            Ok(("0,9".to_owned(), "5,9".to_owned())),
+            pattern("{} -> {}", "0,9 -> 5,9")
+        );
+        assert_eq!(Ok((3,)), pattern("move {n}!", "move 3!"));
+        assert!(pattern::<(u8,)>("move {n}!", "move 3").is_err());
+        assert_eq!(Ok(vec![]), Pattern::new("noop").captures("noop"));
+        assert!(Pattern::new("noop").captures("noop 1").is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn ambiguous_pattern() {
+        Pattern::new("{}{}");
+    }
+}
@@ -0,0 +1,212 @@
+//! Registration of 3D point clouds: find how clouds seen from different places overlap.
+
+use std::{collections::HashMap, hash::Hash, ops::Neg};
+
+use super::{
+    geom::{Rotation, Vec3},
+    intervals::Int,
+};
+
+/// The placement of a point cloud into another frame: rotate, then translate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement<T> {
+    pub rotation: Rotation,
+    pub translation: Vec3<T>,
+}
+
+impl<T: Int + Neg<Output = T>> Placement<T> {
+    pub fn identity() -> Self {
+        Self {
+            rotation: Rotation::IDENTITY,
+            translation: Vec3::new(T::ZERO, T::ZERO, T::ZERO),
+        }
+    }
+
+    /// Move a point into the other frame.
+    pub fn apply(&self, p: Vec3<T>) -> Vec3<T> {
+        self.rotation.apply(p) + self.translation
+    }
+
+    /// The placement applying `self` then `other`.
+    pub fn then(&self, other: &Self) -> Self {
+        Self {
+            rotation: self.rotation.then(&other.rotation),
+            translation: other.apply(self.translation),
+        }
+    }
+}
+
+/// The fingerprint of a pair of points: their absolute differences on each axis, sorted.
+/// It does not depend on how the cloud is placed, so overlapping clouds share fingerprints.
+fn pair_fingerprint<T: Int>(a: Vec3<T>, b: Vec3<T>) -> [T; 3] {
+    let diff = |a: T, b: T| a.max(b) - a.min(b);
+    let mut fingerprint = [diff(a.x, b.x), diff(a.y, b.y), diff(a.z, b.z)];
+    fingerprint.sort_unstable();
+    fingerprint
+}
+
+/// The number of pairs of points having each fingerprint.
+pub fn fingerprints<T: Int + Hash>(points: &[Vec3<T>]) -> HashMap<[T; 3], usize> {
+    let mut fingerprints = HashMap::new();
+    for (i, &a) in points.iter().enumerate() {
+        for &b in &points[i + 1..] {
+            *fingerprints.entry(pair_fingerprint(a, b)).or_default() += 1;
+        }
+    }
+    fingerprints
+}
+
+/// The number of pairs of points that may be the same in both clouds.
+/// If `n` points overlap, at least `n * (n - 1) / 2` pairs are shared.
+pub fn shared_pairs<T: Int + Hash>(
+    a: &HashMap<[T; 3], usize>,
+    b: &HashMap<[T; 3], usize>,
+) -> usize {
+    a.iter()
+        .map(|(f, &n)| n.min(b.get(f).copied().unwrap_or(0)))
+        .sum()
+}
+
+/// Find a placement of `points` into the frame of `reference` making at least
+/// `min_overlap` points coincide, trying every rotation and translation.
+pub fn register<T>(
+    reference: &[Vec3<T>],
+    points: &[Vec3<T>],
+    min_overlap: usize,
+) -> Option<Placement<T>>
+where
+    T: Int + Neg<Output = T> + Hash,
+{
+    for rotation in Rotation::all() {
+        let rotated = points
+            .iter()
+            .map(|&p| rotation.apply(p))
+            .collect::<Vec<_>>();
+
+        // Count the points each translation would make coincide
+        let mut translations = HashMap::new();
+        for &r in reference {
+            for &p in &rotated {
+                let count = translations.entry(r - p).or_insert(0);
+                *count += 1;
+                if *count >= min_overlap {
+                    return Some(Placement {
+                        rotation,
+                        translation: r - p,
+                    });
+                }
+            }
+        }
+    }
+
+    None
+}
+
+/// Place every cloud into the frame of the first one, by chaining the registrations
+/// of overlapping clouds. Clouds not connected to the first one have no placement.
+pub fn register_all<T>(clouds: &[Vec<Vec3<T>>], min_overlap: usize) -> Vec<Option<Placement<T>>>
+where
+    T: Int + Neg<Output = T> + Hash,
+{
+    let mut placements = vec![None; clouds.len()];
+    if clouds.is_empty() {
+        return placements;
+    }
+
+    // Only try to register clouds sharing enough pairs of points
+    let min_pairs = min_overlap * min_overlap.saturating_sub(1) / 2;
+    let fingerprints = clouds.iter().map(|c| fingerprints(c)).collect::<Vec<_>>();
+
+    placements[0] = Some(Placement::identity());
+    let mut queue = vec![0];
+    while let Some(i) = queue.pop() {
+        let placed = placements[i].unwrap();
+        for j in 0..clouds.len() {
+            if placements[j].is_some()
+                || shared_pairs(&fingerprints[i], &fingerprints[j]) < min_pairs
+            {
+                continue;
+            }
+
+            if let Some(placement) = register(&clouds[i], &clouds[j], min_overlap) {
+                placements[j] = Some(placement.then(&placed));
+                queue.push(j);
+            }
+        }
+    }
+
+    placements
+}
+
+#[cfg(test)]
+mod test {
+    use crate::utils::{
+        geom::{Rotation, Vec3},
+        test_rng::{Rng, SEED},
+    };
+
+    use super::{register, register_all, Placement};
+
+    #[test]
+    fn placements() {
+        let rotations = Rotation::all();
+        let a = Placement {
+            rotation: rotations[5],
+            translation: Vec3::new(1, 2, 3),
+        };
+        let b = Placement {
+            rotation: rotations[17],
+            translation: Vec3::new(-4, 0, 7),
+        };
+
+        let p = Vec3::new(10, -20, 30);
+        assert_eq!(b.apply(a.apply(p)), a.then(&b).apply(p));
+        assert_eq!(p, Placement::identity().apply(p));
+    }
+
+    #[test]
+    fn random_clouds() {
+        let mut rng = Rng::new(SEED);
+        let world = (0..60).map(|_| rng.point(-1000..1001)).collect::<Vec<_>>();
+
+        // Three overlapping views of the world, each with its own placement
+        let rotations = Rotation::all();
+        let views = [0..20, 12..40, 30..60]
+            .into_iter()
+            .enumerate()
+            .map(|(i, range)| {
+                let placement = Placement {
+                    rotation: if i == 0 {
+                        Rotation::IDENTITY
+                    } else {
+                        rotations[rng.range(0..24) as usize]
+                    },
+                    translation: if i == 0 {
+                        Vec3::new(0, 0, 0)
+                    } else {
+                        rng.point(-1000..1001)
+                    },
+                };
+                let inverse = Placement {
+                    rotation: placement.rotation.inverse(),
+                    translation: placement.rotation.inverse().apply(-placement.translation),
+                };
+                let points = world[range].iter().map(|&p| inverse.apply(p)).collect();
+                (placement, points)
+            })
+            .collect::<Vec<(Placement<i32>, Vec<_>)>>();
+
+        let clouds = views.iter().map(|(_, c)| c.clone()).collect::<Vec<_>>();
+        let placements = register_all(&clouds, 8);
+        for ((expected, _), placement) in views.iter().zip(placements) {
+            assert_eq!(Some(*expected), placement);
+        }
+
+        // The first and last views do not overlap
+        assert_eq!(None, register(&clouds[0], &clouds[2], 8));
+        assert_eq!(
+            vec![Some(Placement::identity()), None],
+            register_all(&[clouds[0].clone(), clouds[2].clone()], 8)
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use std::{
+    collections::HashSet,
+    fmt::{self, Display, Write},
+};
+
+use owo_colors::Style;
+
+use super::{CheckedYX, Grid, YX};
+
+/// Cells drawn on top of a grid, replacing the glyph and/or style of the rendered cells.
+pub struct Overlay {
+    positions: HashSet<YX>,
+    glyph: Option<char>,
+    style: Option<Style>,
+}
+
+#[cfg(test)]
+impl Overlay {
+    /// Create an overlay over the given positions, which does not change anything by default.
+    pub fn new(positions: impl IntoIterator<Item = YX>) -> Self {
+        Self {
+            positions: positions.into_iter().collect(),
+            glyph: None,
+            style: None,
+        }
+    }
+
+    /// Replace the glyph of the overlaid cells.
+    #[must_use]
+    pub fn glyph(mut self, glyph: char) -> Self {
+        self.glyph = Some(glyph);
+        self
+    }
+
+    /// Replace the style of the overlaid cells.
+    #[must_use]
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+}
+
+/// Give the style of a cell, if it has one.
+type StyleFn<'a, T> = dyn Fn(CheckedYX, &T) -> Option<Style> + 'a;
+
+/// A configurable rendering of a grid, created with [`Grid::render`].
+///
+/// Each cell is drawn with its glyph, then styled if colors are enabled.
+/// Overlays are applied in order, so the last one wins.
+pub struct GridRender<'a, T, G> {
+    grid: &'a Grid<T>,
+    glyph: Box<dyn Fn(&'a T) -> G + 'a>,
+    style: Option<Box<StyleFn<'a, T>>>,
+    overlays: Vec<Overlay>,
+    separator: &'a str,
+    colored: bool,
+}
+
+impl<T> Grid<T> {
+    /// Render the grid, drawing each cell with the glyph returned by the mapping.
+    ///
+    /// By default, cells are separated by nothing and colors are enabled.
+    pub fn render<'a, G: Display>(
+        &'a self,
+        glyph: impl Fn(&'a T) -> G + 'a,
+    ) -> GridRender<'a, T, G> {
+        GridRender {
+            grid: self,
+            glyph: Box::new(glyph),
+            style: None,
+            overlays: vec![],
+            separator: "",
+            colored: true,
+        }
+    }
+}
+
+impl<'a, T, G: Display> GridRender<'a, T, G> {
+    /// Separate the cells of a row by the given string.
+    #[must_use]
+    pub fn separator(mut self, separator: &'a str) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Style each cell with the style returned by the mapping, if any.
+    #[cfg(test)]
+    #[must_use]
+    pub fn style(mut self, style: impl Fn(CheckedYX, &T) -> Option<Style> + 'a) -> Self {
+        self.style = Some(Box::new(style));
+        self
+    }
+
+    /// Draw an overlay on top of the grid.
+    #[cfg(test)]
+    #[must_use]
+    pub fn overlay(mut self, overlay: Overlay) -> Self {
+        self.overlays.push(overlay);
+        self
+    }
+
+    /// Ignore every style to only output plain text, e.g. to compare it in tests.
+    #[cfg(test)]
+    #[must_use]
+    pub fn plain(mut self) -> Self {
+        self.colored = false;
+        self
+    }
+
+    /// Write a single cell with its glyph and style.
+    fn write_cell(&self, f: &mut fmt::Formatter<'_>, pos: CheckedYX) -> fmt::Result {
+        let grid: &'a Grid<T> = self.grid;
+        let cell = grid.get(pos);
+
+        let mut glyph = None;
+        let mut style = self.style.as_ref().and_then(|style| style(pos, cell));
+        for overlay in self.overlays.iter().filter(|o| o.positions.contains(&pos)) {
+            glyph = overlay.glyph.or(glyph);
+            style = overlay.style.or(style);
+        }
+
+        let style = style.filter(|_| self.colored);
+        match (glyph, style) {
+            (Some(glyph), Some(style)) => write!(f, "{}", style.style(glyph)),
+            (Some(glyph), None) => f.write_char(glyph),
+            (None, Some(style)) => write!(f, "{}", style.style((self.glyph)(cell))),
+            (None, None) => write!(f, "{}", (self.glyph)(cell)),
+        }
+    }
+}
+
+impl<T, G: Display> Display for GridRender<'_, T, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for pos in self.grid.coordinates() {
+            let (y, x) = *pos;
+            if x != 0 {
+                f.write_str(self.separator)?;
+            } else if y != 0 {
+                f.write_char('\n')?;
+            }
+            self.write_cell(f, pos)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use owo_colors::Style;
+
+    use crate::utils::Grid;
+
+    use super::Overlay;
+
+    fn grid() -> Grid<u8> {
+        Grid {
+            data: vec![1, 0, 0, 1, 1, 0],
+            width: 3,
+            height: 2,
+        }
+    }
+
+    #[test]
+    fn glyphs() {
+        let g = grid();
+
+        assert_eq!("1 0 0\n1 1 0", g.render(|c| c).separator(" ").to_string());
+        assert_eq!(
+            "#..\n##.",
+            g.render(|&c| if c == 1 { '#' } else { '.' }).to_string()
+        );
+    }
+
+    #[test]
+    fn overlays() {
+        let g = grid();
+        let render = g
+            .render(|c| c)
+            .style(|_, &c| (c == 1).then(|| Style::new().red()))
+            .overlay(Overlay::new([(0, 0), (0, 1)]).glyph('x'))
+            .overlay(Overlay::new([(0, 1), (1, 2)]).glyph('o'));
+
+        assert_eq!("xo0\n11o", render.plain().to_string());
+    }
+
+    #[test]
+    fn colors() {
+        let g = grid();
+        let red = Style::new().red();
+        let render = g
+            .render(|c| c)
+            .style(move |_, &c| (c == 1).then_some(red))
+            .overlay(Overlay::new([(1, 2)]).style(Style::new().bold()));
+
+        assert_eq!(
+            format!(
+                "{}00\n{}{}{}",
+                red.style(1),
+                red.style(1),
+                red.style(1),
+                Style::new().bold().style(0)
+            ),
+            render.to_string()
+        );
+    }
+}
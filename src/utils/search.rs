@@ -0,0 +1,298 @@
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// The result of a graph search.
+///
+/// Contains the lowest cost found to reach each explored node,
+/// and the node it was reached from to reconstruct the paths.
+pub struct Search<N, C> {
+    costs: HashMap<N, C>,
+    parents: HashMap<N, N>,
+    goal: Option<N>,
+}
+
+impl<N: Eq + Hash + Clone, C: Copy> Search<N, C> {
+    /// The first goal node reached by the search, if any.
+    #[cfg(test)]
+    pub fn goal(&self) -> Option<&N> {
+        self.goal.as_ref()
+    }
+
+    /// The cost to reach the goal node, if one was reached.
+    pub fn goal_cost(&self) -> Option<C> {
+        self.goal.as_ref().and_then(|goal| self.cost(goal))
+    }
+
+    /// The path from a start node to the goal node, if one was reached.
+    pub fn goal_path(&self) -> Option<Vec<N>> {
+        self.goal.as_ref().and_then(|goal| self.path(goal))
+    }
+
+    /// The cost to reach a node, if it has been explored.
+    ///
+    /// If the search stopped at a goal, costs of nodes not yet settled may not be the lowest.
+    pub fn cost(&self, node: &N) -> Option<C> {
+        self.costs.get(node).copied()
+    }
+
+    /// The costs of all explored nodes.
+    #[cfg(test)]
+    pub fn costs(&self) -> &HashMap<N, C> {
+        &self.costs
+    }
+
+    /// The path from a start node to the given node (both included), if it has been explored.
+    pub fn path(&self, node: &N) -> Option<Vec<N>> {
+        if !self.costs.contains_key(node) {
+            return None;
+        }
+
+        let mut path = vec![node.clone()];
+        while let Some(parent) = self.parents.get(path.last().unwrap()) {
+            path.push(parent.clone());
+        }
+
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Breadth-first search from one or multiple start nodes, where every move costs 1.
+///
+/// Stop as soon as a goal node is reached. Use `|_| false` to explore the whole graph.
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, usize>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search {
+        costs: HashMap::new(),
+        parents: HashMap::new(),
+        goal: None,
+    };
+
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if search.costs.insert(start.clone(), 0).is_none() {
+            queue.push_back((start, 0));
+        }
+    }
+
+    while let Some((node, cost)) = queue.pop_front() {
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+
+        for next in neighbors(&node) {
+            if let Entry::Vacant(entry) = search.costs.entry(next.clone()) {
+                entry.insert(cost + 1);
+                search.parents.insert(next.clone(), node.clone());
+                queue.push_back((next, cost + 1));
+            }
+        }
+    }
+
+    search
+}
+
+/// A* search from one or multiple start nodes.
+/// The neighbors callback gives the nodes reachable from a node, with the cost to move to each.
+///
+/// The heuristic estimates the remaining cost from a node to the nearest goal.
+/// It must never overestimate it, or the found path may not be the cheapest.
+///
+/// Stop as soon as a goal node is reached. Use `|_| false` to explore the whole graph.
+pub fn astar<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Search<N, C>
+where
+    N: Eq + Hash + Clone,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut search = Search {
+        costs: HashMap::new(),
+        parents: HashMap::new(),
+        goal: None,
+    };
+
+    let mut heap = BinaryHeap::new();
+    for start in starts {
+        let cost = C::default();
+        if search.costs.insert(start.clone(), cost).is_none() {
+            heap.push(HeapItem {
+                priority: heuristic(&start),
+                cost,
+                node: start,
+            });
+        }
+    }
+
+    while let Some(HeapItem { cost, node, .. }) = heap.pop() {
+        // The node has already been reached with a lower cost
+        if search.costs[&node] < cost {
+            continue;
+        }
+
+        if is_goal(&node) {
+            search.goal = Some(node);
+            break;
+        }
+
+        for (next, move_cost) in neighbors(&node) {
+            let next_cost = cost + move_cost;
+            let better = match search.costs.entry(next.clone()) {
+                Entry::Occupied(mut entry) if next_cost < *entry.get() => {
+                    entry.insert(next_cost);
+                    true
+                }
+                Entry::Occupied(_) => false,
+                Entry::Vacant(entry) => {
+                    entry.insert(next_cost);
+                    true
+                }
+            };
+
+            if better {
+                search.parents.insert(next.clone(), node.clone());
+                heap.push(HeapItem {
+                    priority: next_cost + heuristic(&next),
+                    cost: next_cost,
+                    node: next,
+                });
+            }
+        }
+    }
+
+    search
+}
+
+/// A node in the search heap, ordered by lowest priority first.
+struct HeapItem<N, C> {
+    priority: C,
+    cost: C,
+    node: N,
+}
+
+impl<N, C: Ord> PartialEq for HeapItem<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<N, C: Ord> Eq for HeapItem<N, C> {}
+
+impl<N, C: Ord> PartialOrd for HeapItem<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> Ord for HeapItem<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reverse since max-heap and we want lower priorities first.
+        // On equal priorities, prefer the nodes closer to the goal.
+        (&self.priority, &other.cost)
+            .cmp(&(&other.priority, &self.cost))
+            .reverse()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{astar, bfs};
+
+    /// Weighted directed graph:
+    /// ```text
+    /// 0 -1-> 1 -1-> 2 -1-> 3
+    /// 0 -5-> 3
+    /// 4 (unreachable)
+    /// ```
+    fn neighbors(n: &u8) -> Vec<(u8, u32)> {
+        match n {
+            0 => vec![(1, 1), (3, 5)],
+            1 => vec![(2, 1)],
+            2 => vec![(3, 1)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn bfs_fewest_moves() {
+        let search = bfs(
+            [0],
+            |n| neighbors(n).into_iter().map(|(n, _)| n),
+            |&n| n == 3,
+        );
+
+        assert_eq!(Some(&3), search.goal());
+        assert_eq!(Some(1), search.goal_cost());
+        assert_eq!(Some(vec![0, 3]), search.goal_path());
+    }
+
+    #[test]
+    fn bfs_whole_graph() {
+        let search = bfs([0], |n| neighbors(n).into_iter().map(|(n, _)| n), |_| false);
+
+        assert_eq!(None, search.goal());
+        assert_eq!(Some(2), search.cost(&2));
+        assert_eq!(None, search.cost(&4));
+        assert_eq!(4, search.costs().len());
+    }
+
+    #[test]
+    fn cheapest() {
+        let search = astar([0], neighbors, |_| 0, |&n| n == 3);
+
+        assert_eq!(Some(3), search.goal_cost());
+        assert_eq!(Some(vec![0, 1, 2, 3]), search.goal_path());
+        assert_eq!(None, search.path(&4));
+    }
+
+    #[test]
+    fn multi_source() {
+        let search = astar([1, 0, 2], neighbors, |_| 0, |_| false);
+
+        assert_eq!(Some(0), search.cost(&2));
+        assert_eq!(Some(1), search.cost(&3));
+        assert_eq!(Some(vec![2, 3]), search.path(&3));
+    }
+
+    #[test]
+    fn astar_grid() {
+        // 5x5 grid with a wall on x=2 except at y=4
+        const N: i32 = 5;
+        let neighbors = |&(y, x): &(i32, i32)| {
+            [(y - 1, x), (y + 1, x), (y, x - 1), (y, x + 1)]
+                .into_iter()
+                .filter(|&(y, x)| (0..N).contains(&y) && (0..N).contains(&x))
+                .filter(|&(y, x)| x != 2 || y == 4)
+                .map(|p| (p, 1))
+        };
+        let goal = (0, 4);
+        let heuristic = |&(y, x): &(i32, i32)| (goal.0 - y).abs() + (goal.1 - x).abs();
+
+        let search = astar([(0, 0)], neighbors, heuristic, |&p| p == goal);
+        let path = search.goal_path().unwrap();
+
+        assert_eq!(Some(12), search.goal_cost());
+        assert_eq!(13, path.len());
+        assert!(path.contains(&(4, 2)));
+        assert_eq!(
+            search.goal_cost(),
+            astar([(0, 0)], neighbors, |_| 0, |&p| p == goal).goal_cost()
+        );
+    }
+}
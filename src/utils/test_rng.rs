@@ -0,0 +1,61 @@
+use std::{fmt::Debug, ops::Range};
+
+use super::geom::Vec3;
+
+/// The seed of the property tests, so that their inputs are the same on every run.
+pub const SEED: u64 = 0x5EED_CAFE;
+
+/// A small deterministic pseudo-random generator (xorshift64*),
+/// to generate inputs for property tests.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // The state must never be 0
+        Self(seed ^ 0x9E37_79B9_7F4A_7C15)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// A value in the range. The range must not be empty.
+    pub fn range(&mut self, range: Range<u64>) -> u64 {
+        range.start + self.next_u64() % (range.end - range.start)
+    }
+
+    /// A signed value in the range. The range must not be empty.
+    pub fn int<T>(&mut self, range: Range<T>) -> T
+    where
+        T: Into<i64> + TryFrom<i64>,
+        T::Error: Debug,
+    {
+        let (start, end) = (range.start.into(), range.end.into());
+        let value = start + self.range(0..(end - start) as u64) as i64;
+        T::try_from(value).unwrap()
+    }
+
+    /// A range starting in `starts`, with a length in `lengths`.
+    pub fn interval<T>(&mut self, starts: Range<T>, lengths: Range<T>) -> Range<T>
+    where
+        T: Into<i64> + TryFrom<i64>,
+        T::Error: Debug,
+    {
+        let start = self.int(starts).into();
+        let end = start + self.int(lengths).into();
+        T::try_from(start).unwrap()..T::try_from(end).unwrap()
+    }
+
+    /// A point whose coordinates are in the range. The range must not be empty.
+    pub fn point<T>(&mut self, range: Range<T>) -> Vec3<T>
+    where
+        T: Into<i64> + TryFrom<i64> + Clone,
+        T::Error: Debug,
+    {
+        let mut c = || self.int(range.clone());
+        Vec3::new(c(), c(), c())
+    }
+}
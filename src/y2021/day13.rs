@@ -1,105 +1,76 @@
 use std::{
-    cmp::Ordering,
-    collections::BTreeSet,
-    fmt::{self, Display, Write},
+    fmt::{self, Display},
     str::FromStr,
 };
 
-use crate::Solution;
+use crate::{
+    utils::{geom::Vec2, ocr, CheckedYX, Grid},
+    Solution,
+};
 
 pub struct Day13;
 
 /// A coordinate along an axis.
 type Coord = u16;
 
-#[derive(PartialEq, Eq, Clone, Copy, PartialOrd, Ord)]
 /// A 2D position.
-struct XY(Coord, Coord);
-
-impl XY {
-    /// Return the position of the point after the given fold.
-    /// If the point does not exist after the fold, return None.
-    fn folded(self, fold: Fold) -> Option<Self> {
-        match fold {
-            Fold::X(x) => match self.0.cmp(&x) {
-                Ordering::Less => Some(self),
-                Ordering::Equal => None,
-                Ordering::Greater => Some(Self(2 * x - self.0, self.1)),
-            },
-            Fold::Y(y) => match self.1.cmp(&y) {
-                Ordering::Less => Some(self),
-                Ordering::Equal => None,
-                Ordering::Greater => Some(Self(self.0, 2 * y - self.1)),
-            },
-        }
-    }
-}
-
-impl FromStr for XY {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (x, y) = s.split_once(',').ok_or(())?;
+type XY = Vec2<Coord>;
 
-        let x = x.parse().map_err(|_| ())?;
-        let y = y.parse().map_err(|_| ())?;
-
-        Ok(Self(x, y))
-    }
-}
-
-/// A transparent paper sheet, containing a set of distinct points.
-struct Paper {
-    points: BTreeSet<XY>,
-    width: Coord,
-    height: Coord,
-}
+/// A transparent paper sheet, where each cell may contain a point.
+struct Paper(Grid<bool>);
 
 impl Paper {
     /// Fold the paper along an axis and coordinate.
     /// If points are folded to the same position, they will be merged together.
     fn fold(self, fold: Fold) -> Self {
-        let points = self
-            .points
-            .into_iter()
-            .flat_map(|p| p.folded(fold))
-            .collect();
+        match fold {
+            Fold::Y(y) => Self(Self::fold_up(&self.0, y as _)),
+            // Folding left is folding up the transposed paper
+            Fold::X(x) => Self(Self::fold_up(&self.0.transposed(), x as _).transposed()),
+        }
+    }
 
-        let (width, height) = match fold {
-            Fold::X(_) => (self.width / 2, self.height),
-            Fold::Y(_) => (self.width, self.height / 2),
-        };
+    /// Fold the bottom part of the grid (after the row `y`) over its top part.
+    fn fold_up(grid: &Grid<bool>, y: usize) -> Grid<bool> {
+        let width = grid.width;
+        // The paper ends at its last point, which may be before the fold line
+        let mut top = grid.resized(y, width, (0, 0), false);
+        if grid.height <= y + 1 {
+            return top;
+        }
 
-        Self {
-            points,
-            width,
-            height,
+        let bottom = grid
+            .view((y + 1, 0), grid.height - y - 1, width)
+            .unwrap()
+            .to_grid()
+            .flipped_vertical();
+
+        // The bottom part may be smaller, in which case it is aligned on the fold line
+        let offset = y - bottom.height;
+        for (by, bottom_row) in bottom.rows().enumerate() {
+            for (cell, &b) in top.row_mut(offset + by).iter_mut().zip(bottom_row) {
+                *cell |= b;
+            }
         }
+
+        top
     }
 
     /// Count the number of distinct points on the paper.
     fn len(&self) -> usize {
-        self.points.len()
+        self.0.data.iter().filter(|&&b| b).count()
+    }
+}
+
+impl AsRef<Grid<bool>> for Paper {
+    fn as_ref(&self) -> &Grid<bool> {
+        &self.0
     }
 }
 
 impl Display for Paper {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let w = self.width as usize;
-        let h = self.height as usize;
-
-        for y in 0..h {
-            for x in 0..w {
-                f.write_char(if self.points.contains(&XY(x as _, y as _)) {
-                    '█'
-                } else {
-                    ' '
-                })?
-            }
-            f.write_char('\n')?;
-        }
-
-        Ok(())
+        writeln!(f, "{}", self.0.render(|&b| if b { '█' } else { ' ' }))
     }
 }
 
@@ -148,9 +119,9 @@ impl Solution for Day13 {
             paper = paper.fold(fold);
         }
 
-        // The code is some ASCII art, so print it and let the human brain
-        // of the person reading this comment read the letters.
-        format!("\n{paper}")
+        // The code is some ASCII art: read its letters, or else print it
+        // and let the human brain of the person reading this comment read them.
+        ocr::recognize(&paper).unwrap_or_else(|| format!("\n{paper}"))
     }
 }
 
@@ -159,7 +130,7 @@ impl Day13 {
     fn parse_data(data: &str) -> (Paper, impl Iterator<Item = Fold> + '_) {
         let (points, folds) = data.split_once("\n\n").unwrap();
 
-        let points: BTreeSet<XY> = points
+        let points: Vec<XY> = points
             .split_terminator('\n')
             .map(|line| line.parse().expect("Could not parse XY point"))
             .collect();
@@ -168,13 +139,65 @@ impl Day13 {
             .split_terminator('\n')
             .map(|line| line.parse().expect("Could not parse fold instruction"));
 
-        let width = points.iter().map(|p| p.0).max().unwrap();
-        let height = points.iter().map(|p| p.1).max().unwrap();
-        let paper = Paper {
-            points,
-            width,
-            height,
-        };
-        (paper, folds)
+        let width = points.iter().map(|p| p.x).max().unwrap() as usize + 1;
+        let height = points.iter().map(|p| p.y).max().unwrap() as usize + 1;
+        let mut grid = Grid::new(height, width, false);
+        for XY { x, y } in points {
+            let pos = CheckedYX::new(&grid, (y as _, x as _)).unwrap();
+            *grid.get_mut(pos) = true;
+        }
+
+        (Paper(grid), folds)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Solution;
+
+    use super::Day13;
+
+    const DATA: &str = "6,10
+0,14
+9,10
+0,3
+10,4
+4,11
+6,0
+6,12
+4,1
+0,13
+10,12
+3,4
+3,0
+8,4
+1,10
+2,14
+8,10
+9,0
+
+fold along y=7
+fold along x=5";
+
+    #[test]
+    fn q1() {
+        assert_eq!("17", Day13.q1(DATA));
+    }
+
+    #[test]
+    fn q2() {
+        // A square is not a letter, so the paper is drawn instead, one line per row
+        assert_eq!(
+            "\n█████\n█   █\n█   █\n█   █\n█████\n     \n     \n",
+            Day13.q2(DATA)
+        );
+    }
+
+    #[test]
+    fn fold_past_last_point() {
+        // The points end at row 2 and column 1, before both fold lines
+        let data = "0,0\n1,2\n\nfold along y=3\nfold along x=2";
+        assert_eq!("2", Day13.q1(data));
+        assert_eq!("\n█ \n  \n █\n", Day13.q2(data));
     }
 }
@@ -1,7 +1,5 @@
-use std::{cmp::Ordering, collections::BinaryHeap};
-
 use crate::{
-    utils::{CheckedYX, Grid},
+    utils::{search, CheckedYX, Grid},
     Solution,
 };
 
@@ -15,7 +13,7 @@ impl Solution for Day15 {
     fn q1(&self, data: &str) -> String {
         let grid = Self::parse_data(data);
 
-        let risk = Self::min_risk_path(&grid);
+        let (risk, _) = Self::min_risk_path(&grid);
         risk.to_string()
     }
 
@@ -25,27 +23,11 @@ impl Solution for Day15 {
     fn q2(&self, data: &str) -> String {
         let grid = Self::extend_grid(Self::parse_data(data), 5);
 
-        let risk = Self::min_risk_path(&grid);
+        let (risk, _) = Self::min_risk_path(&grid);
         risk.to_string()
     }
 }
 
-#[derive(PartialEq, Eq)]
-struct HeapItem(Risk, CheckedYX);
-
-impl PartialOrd for HeapItem {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for HeapItem {
-    fn cmp(&self, other: &Self) -> Ordering {
-        // Reverse since max-heap and we want lower risks first
-        self.0.cmp(&other.0).reverse()
-    }
-}
-
 impl Day15 {
     /// Parse the grid of digits
     fn parse_data(data: &str) -> Grid<Risk> {
@@ -84,48 +66,88 @@ impl Day15 {
         new_grid
     }
 
-    /// Find the path from top-left to bottom-right that
-    /// minimizes the risk and return its total risk.
-    fn min_risk_path(grid: &Grid<Risk>) -> Risk {
-        let mut risks = grid.clone();
-        risks
-            .coordinates()
-            .for_each(|p| *risks.get_mut(p) = Risk::MAX);
-
+    /// Find the path from top-left to bottom-right that minimizes the risk.
+    /// Return its total risk and its positions, from top-left to bottom-right.
+    fn min_risk_path(grid: &Grid<Risk>) -> (Risk, Vec<CheckedYX>) {
         let topleft = grid.coordinates().next().unwrap();
-        let mut heap = BinaryHeap::with_capacity(1024);
-        heap.push(HeapItem(0, topleft));
-
         let bottom_right = grid.coordinates().last().unwrap();
 
-        while let Some(HeapItem(heap_risk, pos)) = heap.pop() {
-            if pos == bottom_right {
-                return heap_risk;
-            }
+        // Every cell has a risk of at least 1,
+        // so the remaining risk is at least the remaining distance
+        let (ymax, xmax) = *bottom_right;
+        let heuristic = |pos: &CheckedYX| {
+            let (y, x) = **pos;
+            ((ymax - y) + (xmax - x)) as Risk
+        };
 
-            let cell_risk = risks.get_mut(pos);
-            if *cell_risk <= heap_risk {
-                continue;
-            }
-            *cell_risk = heap_risk;
-
-            let mut add_if_better = |pos: Option<CheckedYX>| {
-                if let Some(pos) = pos {
-                    let cur_risk = *risks.get(pos);
-                    let new_risk = *grid.get(pos) + heap_risk;
-
-                    if new_risk < cur_risk {
-                        heap.push(HeapItem(new_risk, pos));
-                    }
-                }
-            };
-
-            add_if_better(grid.bottom(pos));
-            add_if_better(grid.right(pos));
-            add_if_better(grid.top(pos));
-            add_if_better(grid.left(pos));
-        }
+        let neighbors = |&pos: &CheckedYX| {
+            [
+                grid.bottom(pos),
+                grid.right(pos),
+                grid.top(pos),
+                grid.left(pos),
+            ]
+            .into_iter()
+            .flatten()
+            .map(|pos| (pos, *grid.get(pos)))
+        };
 
-        unreachable!()
+        let search = search::astar([topleft], neighbors, heuristic, |&pos| pos == bottom_right);
+        (search.goal_cost().unwrap(), search.goal_path().unwrap())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{utils::render::Overlay, Solution};
+
+    use super::Day15;
+
+    #[test]
+    fn q1() {
+        assert_eq!("40", Day15.q1(DATA));
+    }
+
+    #[test]
+    fn q2() {
+        assert_eq!("315", Day15.q2(DATA));
+    }
+
+    #[test]
+    fn path() {
+        let grid = Day15::parse_data(DATA);
+        let (risk, path) = Day15::min_risk_path(&grid);
+
+        // The risk of the starting position is not counted
+        let path_risk: u16 = path.iter().skip(1).map(|&pos| grid.get(pos)).sum();
+        assert_eq!(risk, path_risk);
+
+        let render = grid
+            .render(|risk| risk)
+            .overlay(Overlay::new(path.iter().map(|pos| **pos)).glyph('*'))
+            .plain();
+        assert_eq!(PATH, render.to_string());
+    }
+
+    const DATA: &str = "1163751742
+1381373672
+2136511328
+3694931569
+7463417111
+1319128137
+1359912421
+3125421639
+1293138521
+2311944581";
+
+    const PATH: &str = "*163751742
+*381373672
+*******328
+369493**69
+7463417*11
+1319128**7
+13599124*1
+31254216*9
+12931385**
+231194458*";
+}
@@ -1,6 +1,9 @@
 use std::iter::{Product, Sum};
 
-use crate::{utils::collect_n_bits, Solution};
+use crate::{
+    utils::bits::{self, BitError, BitReader},
+    Solution,
+};
 
 pub struct Day16;
 
@@ -51,12 +54,11 @@ enum PacketValue {
 }
 
 impl PacketValue {
-    /// Try to extract a packet value from an iterator of bits.
-    /// If the bits begins with a complete packet value, only its bits will be popped out of the iterator.
-    /// On other cases, the iterator may be removed of an undefined number of bits and None will be returned.
-    fn try_extract_from_bits(bits: &mut dyn Iterator<Item = bool>) -> Option<Self> {
+    /// Read a packet value from the bits.
+    /// On error, the reader may have consumed an undefined number of bits.
+    fn read(bits: &mut BitReader) -> Result<Self, BitError> {
         // Parse the packet type id
-        let type_id = collect_n_bits(bits, 3)?;
+        let type_id = bits.read_bits(3)?;
 
         match type_id {
             4 => Self::parse_value(bits),
@@ -67,12 +69,12 @@ impl PacketValue {
     /// Parse a value from the bits.
     /// A value is composed of packets of 5 bits, each one beggining
     /// with a 1, except the last one.
-    fn parse_value(bits: &mut dyn Iterator<Item = bool>) -> Option<Self> {
+    fn parse_value(bits: &mut BitReader) -> Result<Self, BitError> {
         let mut value = 0;
 
         loop {
-            let last = !bits.next()?;
-            let num: u64 = collect_n_bits(bits, 4)?;
+            let last = !bits.read_bit()?;
+            let num: u64 = bits.read_bits(4)?;
 
             value <<= 4;
             value |= num;
@@ -82,30 +84,27 @@ impl PacketValue {
             }
         }
 
-        Some(Self::Value(value))
+        Ok(Self::Value(value))
     }
 
     /// Parse an operator from the bits.
     /// A value is composed of packets of 5 bits, each one beggining
     /// with a 1, except the last one.
-    fn parse_operator(bits: &mut dyn Iterator<Item = bool>, id: u8) -> Option<PacketValue> {
-        let length_id = bits.next()?;
+    fn parse_operator(bits: &mut BitReader, id: u8) -> Result<PacketValue, BitError> {
+        let length_id = bits.read_bit()?;
         let subpackets = match length_id {
             false => {
                 // Subpackets counted by length
-                let len_subpackets: u16 = collect_n_bits(bits, 15)?;
-                let mut sub_bits = bits.take(len_subpackets as _);
-                Day16::parse_packets(&mut sub_bits)
+                let len_subpackets: usize = bits.read_bits(15)?;
+                let mut sub_bits = bits.sub_reader(len_subpackets)?;
+                Day16::parse_packets(&mut sub_bits)?
             }
             true => {
                 // Subpackets counted by number
-                let n_subpackets: u16 = collect_n_bits(bits, 11)?;
-                let mut subpackets = Vec::with_capacity(n_subpackets as _);
-                for _ in 0..n_subpackets {
-                    let packet = Packet::try_extract_from_bits(bits)?;
-                    subpackets.push(packet);
-                }
-                subpackets
+                let n_subpackets: u16 = bits.read_bits(11)?;
+                (0..n_subpackets)
+                    .map(|_| Packet::read(bits))
+                    .collect::<Result<_, _>>()?
             }
         };
 
@@ -120,7 +119,7 @@ impl PacketValue {
             _ => unreachable!(),
         };
 
-        Some(Self::Operator {
+        Ok(Self::Operator {
             operation,
             subpackets,
         })
@@ -134,17 +133,16 @@ struct Packet {
 }
 
 impl Packet {
-    /// Try to extract a packet from an iterator of bits.
-    /// If the bits begins with a complete packet, only its bits will be popped out of the iterator.
-    /// On other cases, the iterator may be removed of an undefined number of bits and None will be returned.
-    fn try_extract_from_bits(bits: &mut dyn Iterator<Item = bool>) -> Option<Self> {
+    /// Read a packet from the bits.
+    /// On error, the reader may have consumed an undefined number of bits.
+    fn read(bits: &mut BitReader) -> Result<Self, BitError> {
         // Parse the version
-        let version = collect_n_bits(bits, 3)?;
+        let version = bits.read_bits(3)?;
 
         // Parse the value
-        let value = PacketValue::try_extract_from_bits(bits)?;
+        let value = PacketValue::read(bits)?;
 
-        Some(Self { version, value })
+        Ok(Self { version, value })
     }
 }
 
@@ -187,24 +185,17 @@ impl Solution for Day16 {
 impl Day16 {
     /// Decode the hexadecimal string into a single packet.
     /// Additional bits are ignored.
-    fn parse_data(data: &str) -> Option<Packet> {
-        let mut bits = Self::parse_hex_to_bits(data.trim().chars());
-        Packet::try_extract_from_bits(&mut bits)
+    fn parse_data(data: &str) -> Result<Packet, BitError> {
+        let bytes = bits::hex_to_bytes(data.trim())?;
+        Packet::read(&mut BitReader::new(&bytes))
     }
 
-    /// Parse a iterator of bits to a list of packets.
-    fn parse_packets(bits: &mut dyn Iterator<Item = bool>) -> Vec<Packet> {
+    /// Parse all the bits into a list of packets.
+    fn parse_packets(bits: &mut BitReader) -> Result<Vec<Packet>, BitError> {
         let mut packets = vec![];
-        while let Some(packet) = Packet::try_extract_from_bits(bits) {
-            packets.push(packet);
+        while !bits.is_empty() {
+            packets.push(Packet::read(bits)?);
         }
-        packets
-    }
-
-    /// Parse hexadecimal characters into their bits representation
-    fn parse_hex_to_bits(chars: impl Iterator<Item = char>) -> impl Iterator<Item = bool> {
-        chars
-            .map(|c| c.to_digit(16).unwrap() as u8)
-            .flat_map(|n| (0..4).rev().map(move |i| ((n >> i) & 1) != 0))
+        Ok(packets)
     }
 }
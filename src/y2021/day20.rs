@@ -151,7 +151,8 @@ impl Day20 {
         let _ = lines.next();
 
         // Parse the input image
-        let image = Image(Grid::from_str_map(lines, |c| c == '#'));
+        let image =
+            Image(Grid::from_str_map(lines, |c| c == '#').expect("Could not parse the image"));
 
         (algorithm, image)
     }
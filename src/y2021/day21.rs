@@ -1,6 +1,7 @@
-use std::collections::HashMap;
-
-use crate::{utils::TryCollectArray, Solution};
+use crate::{
+    utils::{memo::Memo, TryCollectArray},
+    Solution,
+};
 
 pub struct Day21;
 
@@ -92,6 +93,16 @@ impl<const WIN_SCORE: u16, const GRID_SIZE: u32> GameState<WIN_SCORE, GRID_SIZE>
             TurnResult::Continue
         }
     }
+
+    /// A distinct index for each state of a game still in progress,
+    /// below `2 * GRID_SIZE² * WIN_SCORE²`.
+    fn index(&self) -> usize {
+        let (grid, win) = (GRID_SIZE as usize, WIN_SCORE as usize);
+        let [a, b] = &self.pawns;
+        let positions = (a.position as usize - 1) * grid + b.position as usize - 1;
+        let scores = a.score as usize * win + b.score as usize;
+        (self.turn_player2 as usize * grid * grid + positions) * win * win + scores
+    }
 }
 
 impl Solution for Day21 {
@@ -130,15 +141,9 @@ impl Solution for Day21 {
         let pawns = Self::parse_data(data);
         let game = GameState::new(pawns);
 
-        type Cache = HashMap<GameState<21>, [u64; 2]>;
-        let mut cache = HashMap::with_capacity(4096);
-
-        fn play_turn(cache: &mut Cache, state: GameState<21>) -> [u64; 2] {
-            // If the result is already in the cache, simply return it
-            if let Some(r) = cache.get(&state) {
-                return *r;
-            }
-
+        // Simulate all possible games
+        let mut memo: Memo<_, [u64; 2], _> = Memo::dense(GameState::<21>::index);
+        let wins = memo.get(game, &|play_turn, state: &GameState<21>| {
             let mut wins = [0, 0];
             let iplayer = state.turn_player2 as usize;
 
@@ -159,7 +164,7 @@ impl Solution for Day21 {
                     wins[iplayer] += times;
                 } else {
                     // The game continues, the next player will roll the dice
-                    let r = play_turn(cache, state_copy);
+                    let r = play_turn(state_copy);
 
                     // Add the wins to each player
                     wins[0] += times * r[0];
@@ -167,15 +172,8 @@ impl Solution for Day21 {
                 }
             }
 
-            // Save the result to the cache for memoization
-            cache.insert(state, wins);
-
-            // Return the result
             wins
-        }
-
-        // Simulate all possible games
-        let wins = play_turn(&mut cache, game);
+        });
 
         // Return the number of universes in which the player with the most wins wins
         wins.iter().max().unwrap().to_string()
@@ -1,103 +1,33 @@
 use std::{ops::Range, str::FromStr};
 
-use crate::{unwrap_or_continue, utils::TryCollectArray, Solution};
+use crate::{
+    utils::{
+        boxes::{Aabb, SignedBoxes},
+        TryCollectArray,
+    },
+    Solution,
+};
 
 pub struct Day22;
 
-#[derive(Debug, Clone)]
-struct Cuboid {
-    x: Range<i32>,
-    y: Range<i32>,
-    z: Range<i32>,
-}
-
-impl Cuboid {
-    /// Compute the intersection of two cuboids.
-    fn intersect(&self, other: &Self) -> Option<Self> {
-        fn process_axis(ax1: &Range<i32>, ax2: &Range<i32>) -> Range<i32> {
-            ax1.start.max(ax2.start)..ax1.end.min(ax2.end)
-        }
-
-        let x = process_axis(&self.x, &other.x);
-        if x.is_empty() {
-            return None;
-        }
-
-        let y = process_axis(&self.y, &other.y);
-        if y.is_empty() {
-            return None;
-        }
-
-        let z = process_axis(&self.z, &other.z);
-        if z.is_empty() {
-            return None;
-        }
-
-        Some(Self { x, y, z })
-    }
-
-    /// Remove a smaller cuboid from the cuboid, splitting it into multiple smaller parts.
-    /// The returned cuboids do not contain the removed cuboid.
-    fn remove_cuboid(mut self, cub_to_remove: &Self) -> Vec<Self> {
-        let mut res = vec![];
-
-        macro_rules! split_axis {
-            ($ax:ident) => {
-                // Split left
-                if self.$ax.start < cub_to_remove.$ax.start {
-                    res.push(Self {
-                        $ax: self.$ax.start..cub_to_remove.$ax.start,
-                        ..self.clone()
-                    });
-                }
-
-                // Split right
-                if self.$ax.end > cub_to_remove.$ax.end {
-                    res.push(Self {
-                        $ax: cub_to_remove.$ax.end..self.$ax.end,
-                        ..self.clone()
-                    })
-                }
-
-                self.$ax = cub_to_remove.$ax.clone();
-            };
-        }
-
-        split_axis!(x);
-        split_axis!(y);
-        split_axis!(z);
-
-        res
-    }
-
-    /// Return the number of cubes inside the cuboid.
-    fn size(&self) -> usize {
-        self.x.len() * self.y.len() * self.z.len()
-    }
-}
-
-impl FromStr for Cuboid {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let parse_axis = |s: &str| {
-            let (_, s) = s.split_once('=')?;
-            let (start, end) = s.split_once("..")?;
+/// A cuboid of cubes, with (x, y, z) axes.
+type Cuboid = Aabb<i32, 3>;
 
-            let start = start.parse().ok()?;
-            let end = end.parse::<i32>().ok()? + 1;
+/// Parse a cuboid in the `x=A..B,y=C..D,z=E..F` format, where bounds are included.
+fn parse_cuboid(s: &str) -> Option<Cuboid> {
+    let parse_axis = |s: &str| {
+        let (_, s) = s.split_once('=')?;
+        let (start, end) = s.split_once("..")?;
 
-            Some(start..end)
-        };
+        let start = start.parse().ok()?;
+        let end = end.parse::<i32>().ok()? + 1;
 
-        let [x, y, z] = s.split(',').try_collect_array().ok_or(())?;
+        Some(start..end)
+    };
 
-        let x = parse_axis(x).ok_or(())?;
-        let y = parse_axis(y).ok_or(())?;
-        let z = parse_axis(z).ok_or(())?;
+    let [x, y, z] = s.split(',').try_collect_array()?;
 
-        Ok(Self { x, y, z })
-    }
+    Some(Aabb::new([parse_axis(x)?, parse_axis(y)?, parse_axis(z)?]))
 }
 
 #[derive(Debug, Clone)]
@@ -113,131 +43,229 @@ impl FromStr for RebootStep {
         let (power_on, cuboid) = s.split_once(' ').ok_or(())?;
 
         let power_on = power_on == "on";
-        let area = cuboid.parse()?;
+        let cuboid = parse_cuboid(cuboid).ok_or(())?;
 
-        Ok(Self {
-            power_on,
-            cuboid: area,
-        })
+        Ok(Self { power_on, cuboid })
     }
 }
 
-#[derive(Debug)]
-struct World {
-    cuboids: Vec<Cuboid>,
+impl Solution for Day22 {
+    /// Apply all reboot steps only on cubes between -50 and 50 (on each axis, both values included).
+    /// Count the number of cubes powered on.
+    fn q1(&self, data: &str) -> String {
+        const AX_RANGE: Range<i32> = -50..51;
+        let bounds = Aabb::new([AX_RANGE, AX_RANGE, AX_RANGE]);
+
+        // Restrict the cuboids to the specified range
+        let steps = Self::parse_data(data).flat_map(|step| {
+            Some(RebootStep {
+                cuboid: step.cuboid.intersect(&bounds)?,
+                ..step
+            })
+        });
+
+        Self::count_on(steps).to_string()
+    }
+
+    /// Same as q1 but without the range restriction
+    fn q2(&self, data: &str) -> String {
+        let steps = Self::parse_data(data);
+
+        Self::count_on(steps).to_string()
+    }
 }
 
-impl World {
-    fn new() -> Self {
-        Self { cuboids: vec![] }
+impl Day22 {
+    /// Parse the reboot steps
+    fn parse_data(data: &str) -> impl Iterator<Item = RebootStep> + '_ {
+        data.lines()
+            .map(|line| line.parse().expect("Could not parse input line"))
     }
 
-    fn apply(&mut self, step: RebootStep, from_idx: usize) {
-        // Find the first element that intersects with the cuboid
-        let mut intersections = self.cuboids[from_idx..]
-            .iter()
-            .zip(from_idx..)
-            .flat_map(|(cub, idx)| step.cuboid.intersect(cub).map(|int| (idx, int)));
+    /// Apply the reboot steps and count the number of cubes powered on.
+    fn count_on(steps: impl Iterator<Item = RebootStep>) -> i128 {
+        let mut world = SignedBoxes::new();
 
-        if let Some((idx, intersection)) = intersections.next() {
-            // If intersecting and powering on, simply trim the step cuboid instead of
-            // also trimming the intersected cuboid
+        for step in steps {
             if step.power_on {
-                let step_cubs = step.cuboid.remove_cuboid(&intersection);
-                for cuboid in step_cubs {
-                    self.apply(
-                        RebootStep {
-                            power_on: step.power_on,
-                            cuboid,
-                        },
-                        idx,
-                    )
-                }
+                world.add(&step.cuboid);
             } else {
-                // Pop the intersected cuboid
-                let int_cub = self.cuboids.swap_remove(idx);
-                let mut int_cubs = int_cub.remove_cuboid(&intersection);
-
-                // Try to re-apply the step on the rest of the cuboids
-                self.apply(step.clone(), idx);
-
-                // Re-add the non-intersected parts of the intersected cuboid
-                self.cuboids.append(&mut int_cubs);
+                world.remove(&step.cuboid);
             }
-        } else if step.power_on {
-            // If no intersection, apply the step
-            self.cuboids.push(step.cuboid);
         }
+
+        world.volume()
     }
 }
 
-impl Solution for Day22 {
-    /// Apply all reboot steps only on cubes between -50 and 50 (on each axis, both values included).
-    /// Count the number of cubes powered on.
-    fn q1(&self, data: &str) -> String {
-        const AX_RANGE: Range<i32> = -50..51;
+#[cfg(test)]
+mod test {
+    use crate::{
+        utils::{
+            boxes::Aabb,
+            test_rng::{Rng, SEED},
+        },
+        Solution,
+    };
+
+    use super::{Day22, RebootStep};
+
+    /// The implementation before the boxes were shared, splitting the cuboids
+    /// into disjoint pieces, to check the new one against.
+    mod original {
+        use std::ops::Range;
+
+        use crate::utils::intervals;
+
+        #[derive(Debug, Clone)]
+        pub struct Cuboid {
+            pub x: Range<i32>,
+            pub y: Range<i32>,
+            pub z: Range<i32>,
+        }
 
-        let range_to_bounds = |range: Range<i32>| {
-            let start = range.start.max(AX_RANGE.start);
-            let end = range.end.min(AX_RANGE.end);
+        impl Cuboid {
+            /// Compute the intersection of two cuboids.
+            fn intersect(&self, other: &Self) -> Option<Self> {
+                Some(Self {
+                    x: intervals::intersect(&self.x, &other.x)?,
+                    y: intervals::intersect(&self.y, &other.y)?,
+                    z: intervals::intersect(&self.z, &other.z)?,
+                })
+            }
 
-            if start > end {
-                None
-            } else {
-                Some(start..end)
+            /// Remove a smaller cuboid from the cuboid, splitting it into multiple smaller parts.
+            /// The returned cuboids do not contain the removed cuboid.
+            fn remove_cuboid(mut self, cub_to_remove: &Self) -> Vec<Self> {
+                let mut res = vec![];
+
+                macro_rules! split_axis {
+                    ($ax:ident) => {
+                        // Split left
+                        if self.$ax.start < cub_to_remove.$ax.start {
+                            res.push(Self {
+                                $ax: self.$ax.start..cub_to_remove.$ax.start,
+                                ..self.clone()
+                            });
+                        }
+
+                        // Split right
+                        if self.$ax.end > cub_to_remove.$ax.end {
+                            res.push(Self {
+                                $ax: cub_to_remove.$ax.end..self.$ax.end,
+                                ..self.clone()
+                            })
+                        }
+
+                        self.$ax = cub_to_remove.$ax.clone();
+                    };
+                }
+
+                split_axis!(x);
+                split_axis!(y);
+                split_axis!(z);
+
+                res
             }
-        };
 
-        let steps = Self::parse_data(data);
-        let mut world = World::new();
-
-        for RebootStep {
-            power_on,
-            cuboid: area,
-        } in steps
-        {
-            // Restrict the cuboids to the specified range
-            let x = unwrap_or_continue!(range_to_bounds(area.x));
-            let y = unwrap_or_continue!(range_to_bounds(area.y));
-            let z = unwrap_or_continue!(range_to_bounds(area.z));
-
-            let step = RebootStep {
-                power_on,
-                cuboid: Cuboid { x, y, z },
-            };
-            world.apply(step, 0);
+            /// Return the number of cubes inside the cuboid.
+            pub fn size(&self) -> usize {
+                self.x.len() * self.y.len() * self.z.len()
+            }
         }
 
-        world
-            .cuboids
-            .iter()
-            .map(Cuboid::size)
-            .sum::<usize>()
-            .to_string()
-    }
+        #[derive(Debug, Clone)]
+        pub struct RebootStep {
+            pub power_on: bool,
+            pub cuboid: Cuboid,
+        }
 
-    /// Same as q1 but without the range restriction
-    fn q2(&self, data: &str) -> String {
-        let steps = Self::parse_data(data);
-        let mut world = World::new();
+        #[derive(Debug)]
+        pub struct World {
+            pub cuboids: Vec<Cuboid>,
+        }
 
-        for step in steps {
-            world.apply(step, 0);
+        impl World {
+            pub fn new() -> Self {
+                Self { cuboids: vec![] }
+            }
+
+            pub fn apply(&mut self, step: RebootStep, from_idx: usize) {
+                // Find the first element that intersects with the cuboid
+                let mut intersections = self.cuboids[from_idx..]
+                    .iter()
+                    .zip(from_idx..)
+                    .flat_map(|(cub, idx)| step.cuboid.intersect(cub).map(|int| (idx, int)));
+
+                if let Some((idx, intersection)) = intersections.next() {
+                    // If intersecting and powering on, simply trim the step cuboid instead of
+                    // also trimming the intersected cuboid
+                    if step.power_on {
+                        let step_cubs = step.cuboid.remove_cuboid(&intersection);
+                        for cuboid in step_cubs {
+                            self.apply(
+                                RebootStep {
+                                    power_on: step.power_on,
+                                    cuboid,
+                                },
+                                idx,
+                            )
+                        }
+                    } else {
+                        // Pop the intersected cuboid
+                        let int_cub = self.cuboids.swap_remove(idx);
+                        let mut int_cubs = int_cub.remove_cuboid(&intersection);
+
+                        // Try to re-apply the step on the rest of the cuboids
+                        self.apply(step.clone(), idx);
+
+                        // Re-add the non-intersected parts of the intersected cuboid
+                        self.cuboids.append(&mut int_cubs);
+                    }
+                } else if step.power_on {
+                    // If no intersection, apply the step
+                    self.cuboids.push(step.cuboid);
+                }
+            }
         }
+    }
 
-        world
-            .cuboids
-            .iter()
-            .map(Cuboid::size)
-            .sum::<usize>()
-            .to_string()
+    #[test]
+    fn q1() {
+        let data = "on x=10..12,y=10..12,z=10..12
+on x=11..13,y=11..13,z=11..13
+off x=9..11,y=9..11,z=9..11
+on x=10..10,y=10..10,z=10..10
+on x=-54..-50,y=0..0,z=0..0";
+        assert_eq!("40", Day22.q1(data));
+        assert_eq!("44", Day22.q2(data));
     }
-}
 
-impl Day22 {
-    /// Parse the reboot steps
-    fn parse_data(data: &str) -> impl Iterator<Item = RebootStep> + '_ {
-        data.lines()
-            .map(|line| line.parse().expect("Could not parse input line"))
+    #[test]
+    fn against_original() {
+        let mut rng = Rng::new(SEED);
+        for _ in 0..200 {
+            let steps = (0..rng.range(1..20))
+                .map(|_| RebootStep {
+                    power_on: rng.range(0..3) != 0,
+                    cuboid: Aabb::new([(); 3].map(|_| rng.interval(-20..20, 1..15))),
+                })
+                .collect::<Vec<_>>();
+
+            let mut world = original::World::new();
+            for step in &steps {
+                let [x, y, z] = step.cuboid.axes.clone();
+                let cuboid = original::Cuboid { x, y, z };
+                let power_on = step.power_on;
+                world.apply(original::RebootStep { power_on, cuboid }, 0);
+            }
+            let expected = world
+                .cuboids
+                .iter()
+                .map(original::Cuboid::size)
+                .sum::<usize>();
+
+            assert_eq!(expected as i128, Day22::count_on(steps.into_iter()));
+        }
     }
 }
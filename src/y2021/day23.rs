@@ -1,24 +1,374 @@
-use crate::Solution;
+use std::fmt::Display;
+
+use crate::{
+    utils::{parse::ParseError, search},
+    Solution,
+};
 
 pub struct Day23;
 
 impl Solution for Day23 {
-    /// TODO
+    /// Amphipods of 4 types (A, B, C, D) are in the 4 rooms of a burrow, 2 per room.
+    /// They must be organized so that each room only contains a single type: A in the first
+    /// room, B in the second, ...
+    ///
+    /// Amphipods move by stepping into the hallway, never stopping right outside a room,
+    /// then into their own room, if it does not contain other types.
+    /// Each step costs 1, 10, 100 or 1000 energy for A, B, C or D amphipods.
+    ///
+    /// What is the least energy required to organize the amphipods?
     fn q1(&self, data: &str) -> String {
-        let _lines = Self::parse_data(data);
-        String::new()
+        let burrow: Burrow<2> = Self::parse_data(data).unwrap();
+        organize(burrow).energy().to_string()
     }
 
-    /// TODO
+    /// The diagram was folded: 2 more rows of amphipods are between the first and last ones.
+    ///
+    /// What is the least energy required to organize the amphipods?
     fn q2(&self, data: &str) -> String {
-        let _lines = Self::parse_data(data);
-        String::new()
+        let burrow: Burrow<4> = Self::parse_data(&unfold(data)).unwrap();
+        organize(burrow).energy().to_string()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Amphipod {
+    Amber,
+    Bronze,
+    Copper,
+    Desert,
+}
+
+impl Amphipod {
+    /// The energy required to take a step.
+    const fn energy(self) -> u32 {
+        match self {
+            Amphipod::Amber => 1,
+            Amphipod::Bronze => 10,
+            Amphipod::Copper => 100,
+            Amphipod::Desert => 1000,
+        }
+    }
+
+    /// The index of the room the amphipod must go to.
+    const fn room(self) -> usize {
+        self as usize
+    }
+
+    const fn letter(self) -> char {
+        match self {
+            Amphipod::Amber => 'A',
+            Amphipod::Bronze => 'B',
+            Amphipod::Copper => 'C',
+            Amphipod::Desert => 'D',
+        }
+    }
+}
+
+impl TryFrom<char> for Amphipod {
+    type Error = ParseError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'A' => Ok(Amphipod::Amber),
+            'B' => Ok(Amphipod::Bronze),
+            'C' => Ok(Amphipod::Copper),
+            'D' => Ok(Amphipod::Desert),
+            _ => Err(ParseError::InvalidValue {
+                value: c.to_string(),
+                ty: "Amphipod",
+            }),
+        }
+    }
+}
+
+/// The number of cells of the hallway
+const HALLWAY: usize = 11;
+
+/// The hallway cells an amphipod can stop on: the ones not right outside a room
+const STOPS: [usize; 7] = [0, 1, 3, 5, 7, 9, 10];
+
+/// The hallway cell right outside a room.
+const fn entrance(room: usize) -> usize {
+    2 + 2 * room
+}
+
+/// The amphipods in the burrow, whose rooms are `DEPTH` cells deep.
+/// Room cells are ordered from the one next to the hallway to the deepest one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Burrow<const DEPTH: usize> {
+    hallway: [Option<Amphipod>; HALLWAY],
+    rooms: [[Option<Amphipod>; DEPTH]; 4],
+}
+
+impl<const DEPTH: usize> Burrow<DEPTH> {
+    /// Whether each room is full of its own amphipods.
+    fn is_organized(&self) -> bool {
+        self.rooms.iter().enumerate().all(|(i, room)| {
+            room.iter()
+                .all(|cell| cell.is_some_and(|amphipod| amphipod.room() == i))
+        })
+    }
+
+    /// Whether the room only contains its own amphipods, which never need to move again.
+    fn is_settled(&self, room: usize) -> bool {
+        self.rooms[room]
+            .iter()
+            .flatten()
+            .all(|amphipod| amphipod.room() == room)
+    }
+
+    /// Whether the hallway is free to walk from `from` (excluded) to `to` (included).
+    fn is_clear(&self, from: usize, to: usize) -> bool {
+        let cells = if from < to {
+            from + 1..to + 1
+        } else {
+            to..from
+        };
+        self.hallway[cells].iter().all(Option::is_none)
+    }
+
+    /// The burrows reachable by moving a single amphipod, with the energy it requires.
+    fn moves(&self) -> Vec<(Self, u32)> {
+        // Moving an amphipod into its own room is never worse than anything else,
+        // so only consider this move if there is one
+        for (x, cell) in self.hallway.iter().enumerate() {
+            let Some(amphipod) = *cell else { continue };
+            let room = amphipod.room();
+            if !self.is_settled(room) || !self.is_clear(x, entrance(room)) {
+                continue;
+            }
+
+            let depth = self.rooms[room].iter().rposition(Option::is_none).unwrap();
+            let steps = x.abs_diff(entrance(room)) + depth + 1;
+
+            let mut next = *self;
+            next.hallway[x] = None;
+            next.rooms[room][depth] = Some(amphipod);
+            return vec![(next, steps as u32 * amphipod.energy())];
+        }
+
+        // Otherwise, move the first amphipod of a room to stop in the hallway
+        let mut moves = vec![];
+        for room in (0..4).filter(|&room| !self.is_settled(room)) {
+            let Some(depth) = self.rooms[room].iter().position(Option::is_some) else {
+                continue;
+            };
+            let amphipod = self.rooms[room][depth].unwrap();
+
+            for x in STOPS {
+                if self.is_clear(entrance(room), x) {
+                    let steps = depth + 1 + x.abs_diff(entrance(room));
+
+                    let mut next = *self;
+                    next.rooms[room][depth] = None;
+                    next.hallway[x] = Some(amphipod);
+                    moves.push((next, steps as u32 * amphipod.energy()));
+                }
+            }
+        }
+        moves
+    }
+
+    /// A lower bound of the energy left to organize the burrow: the energy required
+    /// to move each misplaced amphipod into its room, if nothing was in its way.
+    fn min_energy_left(&self) -> u32 {
+        let hallway = self.hallway.iter().enumerate().filter_map(|(x, cell)| {
+            let amphipod = (*cell)?;
+            let steps = x.abs_diff(entrance(amphipod.room())) + 1;
+            Some(steps as u32 * amphipod.energy())
+        });
+
+        let rooms = self.rooms.iter().enumerate().flat_map(|(room, cells)| {
+            cells.iter().enumerate().filter_map(move |(depth, cell)| {
+                let amphipod = (*cell).filter(|a| a.room() != room)?;
+                let steps = depth + 1 + entrance(room).abs_diff(entrance(amphipod.room())) + 1;
+                Some(steps as u32 * amphipod.energy())
+            })
+        });
+
+        hallway.chain(rooms).sum()
     }
 }
 
+impl<const DEPTH: usize> Display for Burrow<DEPTH> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let letter = |cell: &Option<Amphipod>| cell.map_or('.', Amphipod::letter);
+
+        writeln!(f, "{}", "#".repeat(HALLWAY + 2))?;
+        writeln!(
+            f,
+            "#{}#",
+            self.hallway.iter().map(letter).collect::<String>()
+        )?;
+        for depth in 0..DEPTH {
+            // The first row is as wide as the hallway
+            let (left, right) = if depth == 0 { ("##", "##") } else { ("  ", "") };
+            write!(f, "{left}")?;
+            for room in &self.rooms {
+                write!(f, "#{}", letter(&room[depth]))?;
+            }
+            writeln!(f, "#{right}")?;
+        }
+        write!(f, "  {}", "#".repeat(9))
+    }
+}
+
+/// The burrows from the start to the organized one, with the energy spent to reach each.
+struct Plan<const DEPTH: usize> {
+    steps: Vec<(Burrow<DEPTH>, u32)>,
+}
+
+impl<const DEPTH: usize> Plan<DEPTH> {
+    /// The total energy spent.
+    fn energy(&self) -> u32 {
+        self.steps.last().map_or(0, |&(_, energy)| energy)
+    }
+}
+
+impl<const DEPTH: usize> Display for Plan<DEPTH> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (burrow, energy)) in self.steps.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "Energy spent: {energy}")?;
+            writeln!(f, "{burrow}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Find the cheapest way to organize the amphipods.
+fn organize<const DEPTH: usize>(start: Burrow<DEPTH>) -> Plan<DEPTH> {
+    let search = search::astar(
+        [start],
+        Burrow::moves,
+        Burrow::min_energy_left,
+        Burrow::is_organized,
+    );
+
+    let path = search
+        .goal_path()
+        .expect("The amphipods cannot be organized");
+    Plan {
+        steps: path
+            .into_iter()
+            .map(|burrow| (burrow, search.cost(&burrow).unwrap()))
+            .collect(),
+    }
+}
+
+/// Insert the 2 folded rows of amphipods after the first one.
+fn unfold(data: &str) -> String {
+    let mut lines = data.lines().collect::<Vec<_>>();
+    let at = 3.min(lines.len());
+    lines.splice(at..at, ["  #D#C#B#A#", "  #D#B#A#C#"]);
+    lines.join("\n")
+}
+
 impl Day23 {
-    /// TODO
-    fn parse_data(data: &str) -> impl Iterator<Item = &str> + '_ {
-        data.split_terminator('\n')
+    /// Parse the diagram of the burrow, whose rooms must be `DEPTH` deep.
+    fn parse_data<const DEPTH: usize>(data: &str) -> Result<Burrow<DEPTH>, ParseError> {
+        let mismatch = |expected: &str, found: &str| ParseError::Mismatch {
+            expected: expected.to_owned(),
+            found: found.to_owned(),
+        };
+        let cell = |c: char| match c {
+            '.' => Ok(None),
+            c => Amphipod::try_from(c).map(Some),
+        };
+
+        let mut lines = data.lines().skip(1);
+        let hallway = lines.next().unwrap_or_default();
+        let cells = hallway.trim().trim_matches('#');
+        if cells.chars().count() != HALLWAY {
+            return Err(mismatch("a hallway of 11 cells", hallway).at_line(2));
+        }
+
+        let mut burrow = Burrow {
+            hallway: [None; HALLWAY],
+            rooms: [[None; DEPTH]; 4],
+        };
+        for (x, c) in cells.chars().enumerate() {
+            burrow.hallway[x] = cell(c).map_err(|e| e.at_line(2))?;
+        }
+
+        for depth in 0..DEPTH {
+            let line = lines.next().unwrap_or_default();
+            let cells = line
+                .split('#')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>();
+            if cells.len() != 4 || cells.iter().any(|s| s.chars().count() != 1) {
+                return Err(mismatch("a row of 4 room cells", line).at_line(depth + 3));
+            }
+
+            for (room, s) in cells.into_iter().enumerate() {
+                let c = s.chars().next().unwrap();
+                burrow.rooms[room][depth] = cell(c).map_err(|e| e.at_line(depth + 3))?;
+            }
+        }
+
+        Ok(burrow)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Solution;
+
+    use super::{organize, unfold, Burrow, Day23};
+
+    const DATA: &str = "#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########";
+
+    #[test]
+    fn q1() {
+        assert_eq!("12521", Day23.q1(DATA));
+    }
+
+    #[test]
+    fn q2() {
+        assert_eq!("44169", Day23.q2(DATA));
+    }
+
+    #[test]
+    fn diagram() {
+        let burrow: Burrow<4> = Day23::parse_data(&unfold(DATA)).unwrap();
+        assert_eq!(
+            "#############
+#...........#
+###B#C#B#D###
+  #D#C#B#A#
+  #D#B#A#C#
+  #A#D#C#A#
+  #########",
+            burrow.to_string()
+        );
+
+        assert!(Day23::parse_data::<3>(DATA).is_err());
+        assert!(Day23::parse_data::<2>(&DATA.replace('D', "E")).is_err());
+    }
+
+    #[test]
+    fn plan() {
+        let burrow: Burrow<2> = Day23::parse_data(DATA).unwrap();
+        let plan = organize(burrow).to_string();
+
+        assert!(plan.starts_with(&format!("Energy spent: 0\n{DATA}\n")));
+        assert!(plan.ends_with(
+            "Energy spent: 12521
+#############
+#...........#
+###A#B#C#D###
+  #A#B#C#D#
+  #########
+"
+        ));
     }
 }
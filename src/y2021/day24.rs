@@ -1,24 +1,364 @@
-use crate::Solution;
+use std::{fmt::Display, str::FromStr};
+
+use crate::{
+    utils::parse::{self, ParseError},
+    Solution,
+};
 
 pub struct Day24;
 
 impl Solution for Day24 {
-    /// TODO
+    /// The ALU runs the MONAD program, which reads the 14 digits (from 1 to 9) of a model number
+    /// and validates it by leaving 0 in the `z` register.
+    ///
+    /// What is the largest valid model number?
     fn q1(&self, data: &str) -> String {
-        let _lines = Self::parse_data(data);
-        String::new()
+        let program = Self::parse_data(data).unwrap();
+        let digits = model_number(&program, Bound::Largest);
+        digits.iter().map(|d| d.to_string()).collect()
     }
 
-    /// TODO
+    /// What is the smallest valid model number?
     fn q2(&self, data: &str) -> String {
-        let _lines = Self::parse_data(data);
-        String::new()
+        let program = Self::parse_data(data).unwrap();
+        let digits = model_number(&program, Bound::Smallest);
+        digits.iter().map(|d| d.to_string()).collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Register {
+    W,
+    X,
+    Y,
+    Z,
+}
+
+impl FromStr for Register {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "w" => Ok(Register::W),
+            "x" => Ok(Register::X),
+            "y" => Ok(Register::Y),
+            "z" => Ok(Register::Z),
+            _ => Err(ParseError::InvalidValue {
+                value: s.to_owned(),
+                ty: "Register",
+            }),
+        }
+    }
+}
+
+/// The second operand of an instruction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operand {
+    Register(Register),
+    Value(i64),
+}
+
+impl FromStr for Operand {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse()
+            .map(Operand::Register)
+            .or_else(|_| parse::value(s).map(Operand::Value))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Instruction {
+    /// Read an input value into the register
+    Inp(Register),
+    Add(Register, Operand),
+    Mul(Register, Operand),
+    /// Integer division, rounding towards zero
+    Div(Register, Operand),
+    Mod(Register, Operand),
+    /// Store 1 if both values are equal, 0 otherwise
+    Eql(Register, Operand),
+}
+
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words = s.split_whitespace().collect::<Vec<_>>();
+        let binary = |f: fn(Register, Operand) -> Self| match words[1..] {
+            [a, b] => Ok(f(a.parse()?, b.parse()?)),
+            _ => Err(ParseError::Mismatch {
+                expected: format!("{} a b", words[0]),
+                found: s.to_owned(),
+            }),
+        };
+
+        match words.first().copied() {
+            Some("inp") if words.len() == 2 => Ok(Instruction::Inp(words[1].parse()?)),
+            Some("add") => binary(Instruction::Add),
+            Some("mul") => binary(Instruction::Mul),
+            Some("div") => binary(Instruction::Div),
+            Some("mod") => binary(Instruction::Mod),
+            Some("eql") => binary(Instruction::Eql),
+            _ => Err(ParseError::Mismatch {
+                expected: "an ALU instruction".to_owned(),
+                found: s.to_owned(),
+            }),
+        }
+    }
+}
+
+/// An error stopping the ALU
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum AluError {
+    /// An `inp` instruction has no input left to read
+    MissingInput { pc: usize },
+    /// A `div` by 0, or a `mod` of a negative value or by a non-positive one
+    InvalidOperation { pc: usize },
+}
+
+impl Display for AluError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingInput { pc } => write!(f, "instruction {pc} has no input to read"),
+            Self::InvalidOperation { pc } => write!(f, "instruction {pc} is undefined"),
+        }
+    }
+}
+
+/// The Arithmetic Logic Unit: 4 integer registers, all starting at 0.
+#[derive(Debug, Default)]
+struct Alu {
+    registers: [i64; 4],
+}
+
+impl Alu {
+    fn get(&self, register: Register) -> i64 {
+        self.registers[register as usize]
+    }
+
+    fn value(&self, operand: Operand) -> i64 {
+        match operand {
+            Operand::Register(register) => self.get(register),
+            Operand::Value(value) => value,
+        }
+    }
+
+    /// Run a program on fresh registers, reading its inputs in order.
+    fn run(
+        program: &[Instruction],
+        inputs: impl IntoIterator<Item = i64>,
+    ) -> Result<Self, AluError> {
+        let mut alu = Self::default();
+        let mut inputs = inputs.into_iter();
+
+        for (pc, &instruction) in program.iter().enumerate() {
+            let (a, result) = match instruction {
+                Instruction::Inp(a) => (a, inputs.next()),
+                Instruction::Add(a, b) => (a, Some(alu.get(a) + alu.value(b))),
+                Instruction::Mul(a, b) => (a, Some(alu.get(a) * alu.value(b))),
+                Instruction::Div(a, b) => (a, alu.get(a).checked_div(alu.value(b))),
+                Instruction::Mod(a, b) => {
+                    let (a_value, b_value) = (alu.get(a), alu.value(b));
+                    let valid = a_value >= 0 && b_value > 0;
+                    (a, valid.then(|| a_value % b_value))
+                }
+                Instruction::Eql(a, b) => (a, Some((alu.get(a) == alu.value(b)) as i64)),
+            };
+
+            alu.registers[a as usize] = result.ok_or(match instruction {
+                Instruction::Inp(_) => AluError::MissingInput { pc },
+                _ => AluError::InvalidOperation { pc },
+            })?;
+        }
+
+        Ok(alu)
+    }
+}
+
+/// The part of MONAD checking a digit. `z` is used as a stack of base 26 numbers:
+/// a block either pushes `digit + offset`, or pops a value and pushes `digit + offset`
+/// back only if `popped + check != digit`. A valid number must leave the stack empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Block {
+    pops: bool,
+    check: i64,
+    offset: i64,
+}
+
+impl Block {
+    /// The instructions of a block, with the 3 parameters as `{}`.
+    const TEMPLATE: [&'static str; 18] = [
+        "inp w", "mul x 0", "add x z", "mod x 26", "div z {}", "add x {}", "eql x w", "eql x 0",
+        "mul y 0", "add y 25", "mul y x", "add y 1", "mul z y", "mul y 0", "add y w", "add y {}",
+        "mul y x", "add z y",
+    ];
+
+    /// Recognize a block from its instructions, or None if they do not match the template.
+    fn recognize(instructions: &[Instruction]) -> Option<Self> {
+        if instructions.len() != Self::TEMPLATE.len() {
+            return None;
+        }
+
+        let mut parameters = vec![];
+        for (&instruction, template) in instructions.iter().zip(Self::TEMPLATE) {
+            let line = if template.contains("{}") {
+                let (Instruction::Div(_, Operand::Value(value))
+                | Instruction::Add(_, Operand::Value(value))) = instruction
+                else {
+                    return None;
+                };
+                parameters.push(value);
+                template.replace("{}", &value.to_string())
+            } else {
+                template.to_owned()
+            };
+
+            if instruction != line.parse().ok()? {
+                return None;
+            }
+        }
+
+        let pops = match parameters[0] {
+            1 => false,
+            26 => true,
+            _ => return None,
+        };
+        Some(Self {
+            pops,
+            check: parameters[1],
+            offset: parameters[2],
+        })
     }
 }
 
+/// Which valid model number to find
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bound {
+    Largest,
+    Smallest,
+}
+
+/// Find the largest or smallest valid model number, by analyzing the blocks of MONAD.
+///
+/// A pushing block must be matched with a popping block, or the stack would not be empty
+/// at the end. The popped value then must be equal to the digit of the popping block, so
+/// `pushed digit + push offset + pop check = popped digit`. The digits of every pair
+/// are chosen as high (or low) as this constraint allows.
+fn model_number(program: &[Instruction], bound: Bound) -> Vec<i64> {
+    let blocks = program
+        .chunks(Block::TEMPLATE.len())
+        .map(|instructions| Block::recognize(instructions).expect("Unknown block of MONAD"))
+        .collect::<Vec<_>>();
+
+    let mut digits = vec![0; blocks.len()];
+    let mut stack = vec![];
+    for (j, block) in blocks.iter().enumerate() {
+        if !block.pops {
+            stack.push(j);
+            continue;
+        }
+
+        let i = stack.pop().expect("A block pops from an empty stack");
+        let diff = blocks[i].offset + block.check;
+        let (di, dj) = match bound {
+            Bound::Largest => (9.min(9 - diff), 9.min(9 + diff)),
+            Bound::Smallest => (1.max(1 - diff), 1.max(1 + diff)),
+        };
+        assert!((1..=9).contains(&di), "The digits cannot differ by {diff}");
+        (digits[i], digits[j]) = (di, dj);
+    }
+    assert!(stack.is_empty(), "The stack cannot be emptied");
+
+    // Check the found number with the program itself
+    let alu = Alu::run(program, digits.iter().copied()).expect("MONAD could not run");
+    assert_eq!(0, alu.get(Register::Z), "MONAD did not validate the number");
+
+    digits
+}
+
 impl Day24 {
-    /// TODO
-    fn parse_data(data: &str) -> impl Iterator<Item = &str> + '_ {
-        data.split_terminator('\n')
+    /// Parse the instructions of the program.
+    fn parse_data(data: &str) -> Result<Vec<Instruction>, ParseError> {
+        parse::lines(data, parse::value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use itertools::Itertools;
+
+    use crate::Solution;
+
+    use super::{model_number, Alu, AluError, Block, Bound, Day24, Register};
+
+    /// A MONAD-like program made of the blocks with the given parameters.
+    fn monad(blocks: &[(i64, i64, i64)]) -> String {
+        blocks
+            .iter()
+            .flat_map(|&(div, check, offset)| {
+                let mut parameters = [div, check, offset].into_iter();
+                Block::TEMPLATE.map(|line| match line.strip_suffix("{}") {
+                    Some(prefix) => format!("{prefix}{}", parameters.next().unwrap()),
+                    None => line.to_owned(),
+                })
+            })
+            .join("\n")
+    }
+
+    #[test]
+    fn interpreter() {
+        let negate = Day24::parse_data("inp x\nmul x -1").unwrap();
+        assert_eq!(-7, Alu::run(&negate, [7]).unwrap().get(Register::X));
+
+        let three_times = Day24::parse_data("inp z\ninp x\nmul z 3\neql z x").unwrap();
+        assert_eq!(1, Alu::run(&three_times, [4, 12]).unwrap().get(Register::Z));
+        assert_eq!(0, Alu::run(&three_times, [4, 11]).unwrap().get(Register::Z));
+
+        let binary = Day24::parse_data(
+            "inp w\nadd z w\nmod z 2\ndiv w 2\nadd y w\nmod y 2\ndiv w 2\nadd x w\nmod x 2\ndiv w 2\nmod w 2",
+        )
+        .unwrap();
+        // 11 is 0b1011, with the registers in order w, x, y, z
+        assert_eq!([1, 0, 1, 1], Alu::run(&binary, [11]).unwrap().registers);
+
+        assert_eq!(
+            Err(AluError::MissingInput { pc: 1 }),
+            Alu::run(&three_times, [4]).map(|_| ())
+        );
+        let div_zero = Day24::parse_data("inp x\ndiv x y").unwrap();
+        assert_eq!(
+            Err(AluError::InvalidOperation { pc: 1 }),
+            Alu::run(&div_zero, [4]).map(|_| ())
+        );
+        assert!(Day24::parse_data("inp x\nsub x 1").is_err());
+    }
+
+    #[test]
+    fn analysis() {
+        let data = monad(&[(1, 12, 4), (1, 11, 11), (26, -5, 1), (26, -3, 2)]);
+        let program = Day24::parse_data(&data).unwrap();
+
+        // Compare with all the numbers accepted by the program
+        let valid = (0..4)
+            .map(|_| 1..=9)
+            .multi_cartesian_product()
+            .filter(|digits| {
+                let alu = Alu::run(&program, digits.iter().copied()).unwrap();
+                alu.get(Register::Z) == 0
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            valid.last().unwrap(),
+            &model_number(&program, Bound::Largest)
+        );
+        assert_eq!(
+            valid.first().unwrap(),
+            &model_number(&program, Bound::Smallest)
+        );
+        assert_eq!("8399", Day24.q1(&data));
+        assert_eq!("1172", Day24.q2(&data));
     }
 }
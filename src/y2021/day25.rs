@@ -1,24 +1,144 @@
-use crate::Solution;
+use std::fmt::Display;
+
+use crate::{
+    utils::{CheckedYX, Grid, ParseGridError},
+    Solution,
+};
 
 pub struct Day25;
 
 impl Solution for Day25 {
-    /// TODO
+    /// Two herds of sea cucumbers move on the sea floor, which wraps around its edges.
+    /// At each step, every cucumber of the east-facing herd moves one cell east at the same
+    /// time, if that cell is empty. Then the same happens for the south-facing herd.
+    ///
+    /// What is the first step on which no sea cucumber moves?
     fn q1(&self, data: &str) -> String {
-        let _lines = Self::parse_data(data);
-        String::new()
+        let mut floor = Self::parse_data(data).unwrap();
+
+        let mut steps = 1;
+        while floor.step() {
+            steps += 1;
+        }
+        steps.to_string()
+    }
+
+    /// There is no second question on the last day: the sleigh can be started.
+    fn q2(&self, _data: &str) -> String {
+        "Merry Christmas!".to_owned()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Cell {
+    Empty,
+    East,
+    South,
+}
+
+impl TryFrom<char> for Cell {
+    type Error = ();
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '.' => Ok(Cell::Empty),
+            '>' => Ok(Cell::East),
+            'v' => Ok(Cell::South),
+            _ => Err(()),
+        }
     }
+}
 
-    /// TODO
-    fn q2(&self, data: &str) -> String {
-        let _lines = Self::parse_data(data);
-        String::new()
+impl From<Cell> for char {
+    fn from(cell: Cell) -> Self {
+        match cell {
+            Cell::Empty => '.',
+            Cell::East => '>',
+            Cell::South => 'v',
+        }
+    }
+}
+
+/// The sea floor, with the sea cucumbers on it.
+struct SeaFloor(Grid<Cell>);
+
+impl SeaFloor {
+    /// Move both herds, and return whether any sea cucumber moved.
+    fn step(&mut self) -> bool {
+        let east = self.move_herd(Cell::East, (0, 1));
+        let south = self.move_herd(Cell::South, (1, 0));
+        east || south
+    }
+
+    /// Move all the cucumbers of a herd at the same time, and return whether any moved.
+    fn move_herd(&mut self, herd: Cell, (dy, dx): (usize, usize)) -> bool {
+        let grid = &self.0;
+        let moves = grid
+            .coordinates()
+            .filter(|&pos| *grid.get(pos) == herd)
+            .filter_map(|pos| {
+                let (y, x) = *pos;
+                let next = ((y + dy) % grid.height, (x + dx) % grid.width);
+                let next = CheckedYX::new(grid, next).unwrap();
+                (*grid.get(next) == Cell::Empty).then_some((pos, next))
+            })
+            .collect::<Vec<_>>();
+
+        for &(from, to) in &moves {
+            *self.0.get_mut(from) = Cell::Empty;
+            *self.0.get_mut(to) = herd;
+        }
+        !moves.is_empty()
+    }
+}
+
+impl Display for SeaFloor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.render(|&cell| char::from(cell)).fmt(f)
     }
 }
 
 impl Day25 {
-    /// TODO
-    fn parse_data(data: &str) -> impl Iterator<Item = &str> + '_ {
-        data.split_terminator('\n')
+    /// Parse the map of the sea floor.
+    fn parse_data(data: &str) -> Result<SeaFloor, ParseGridError> {
+        Grid::from_chars(data).map(SeaFloor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Solution;
+
+    use super::Day25;
+
+    const DATA: &str = "v...>>.vv>
+.vv>>.vv..
+>>.>v>...v
+>>v>>.>.v.
+v>v.vv.v..
+>.>>..v...
+.vv..>.>v.
+v.v..>>v.v
+....v..v.>";
+
+    #[test]
+    fn q1() {
+        assert_eq!("58", Day25.q1(DATA));
+    }
+
+    #[test]
+    fn steps() {
+        let mut floor = Day25::parse_data("...>>>>>...").unwrap();
+        assert!(floor.step());
+        assert_eq!("...>>>>.>..", floor.to_string());
+        assert!(floor.step());
+        assert_eq!("...>>>.>.>.", floor.to_string());
+
+        // Cucumbers wrap around the edges, and the east herd moves first
+        let mut floor = Day25::parse_data("..>\nv..\n.v.").unwrap();
+        floor.step();
+        assert_eq!(">v.\n...\nv..", floor.to_string());
+
+        assert!(Day25::parse_data("..>\n.x.").is_err());
     }
 }
@@ -1,27 +1,17 @@
 use std::str::FromStr;
 
-use crate::Solution;
+use crate::{
+    utils::{
+        geom::Vec2,
+        parse::{self, ParseError},
+    },
+    Solution,
+};
 
 pub struct Day5;
 
 /// A 2D point
-#[derive(PartialEq, Eq, Hash)]
-struct Point {
-    x: u64,
-    y: u64,
-}
-
-impl FromStr for Point {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (x, y) = s.split_once(',').unwrap();
-        let x = x.parse().unwrap();
-        let y = y.parse().unwrap();
-
-        Ok(Self { x, y })
-    }
-}
+type Point = Vec2<u64>;
 
 /// A 2D line
 #[derive(PartialEq, Eq, Hash)]
@@ -37,12 +27,10 @@ enum Line {
 }
 
 impl FromStr for Line {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (start, end) = s.split_once(" -> ").unwrap();
-        let start: Point = start.parse().unwrap();
-        let end: Point = end.parse().unwrap();
+        let (start, end): (Point, Point) = parse::pattern("{start} -> {end}", s)?;
 
         if start.x == end.x {
             let x = start.x;
@@ -76,8 +64,8 @@ impl Line {
     /// Iterate over the line segment points
     fn walk(&self) -> Box<dyn Iterator<Item = Point>> {
         match *self {
-            Line::Horizontal { y, x1, x2 } => Box::new((x1..=x2).map(move |x| Point { x, y })),
-            Line::Vertical { x, y1, y2 } => Box::new((y1..=y2).map(move |y| Point { x, y })),
+            Line::Horizontal { y, x1, x2 } => Box::new((x1..=x2).map(move |x| Point::new(x, y))),
+            Line::Vertical { x, y1, y2 } => Box::new((y1..=y2).map(move |y| Point::new(x, y))),
             Line::Any { x1, x2, y1, y2 } => {
                 let itx = x1..=x2;
                 let ity: Box<dyn Iterator<Item = u64>> = if y1 <= y2 {
@@ -85,7 +73,7 @@ impl Line {
                 } else {
                     Box::new((y2..=y1).rev())
                 };
-                Box::new(itx.zip(ity).map(move |(x, y)| Point { x, y }))
+                Box::new(itx.zip(ity).map(move |(x, y)| Point::new(x, y)))
             }
         }
     }
@@ -192,7 +180,9 @@ impl Solution for Day5 {
 
 impl Day5 {
     /// Parse all line segments
-    fn parse_data(data: &str) -> impl Iterator<Item = Line> + '_ {
-        data.split_terminator('\n').map(|s| s.parse().unwrap())
+    fn parse_data(data: &str) -> impl Iterator<Item = Line> {
+        parse::lines(data, str::parse)
+            .expect("Could not parse line segments")
+            .into_iter()
     }
 }
@@ -2,7 +2,7 @@ use std::{cmp::Ordering, collections::BinaryHeap};
 
 use itertools::Itertools;
 
-use crate::Solution;
+use crate::{utils::parse, Solution};
 
 pub struct Day6;
 
@@ -28,8 +28,7 @@ impl PartialEq for Fish {
 
 impl PartialOrd for Fish {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        // The lower the timer is, the higher the priority is
-        self.timer.partial_cmp(&other.timer).map(Ordering::reverse)
+        Some(self.cmp(other))
     }
 }
 
@@ -139,7 +138,9 @@ impl Solution for Day6 {
 
 impl Day6 {
     /// Parse the fishes described by their timers
-    fn parse_data(data: &str) -> impl Iterator<Item = Tick> + '_ {
-        data.trim_end().split(',').map(|s| s.parse().unwrap())
+    fn parse_data(data: &str) -> impl Iterator<Item = Tick> {
+        parse::list(data, ",")
+            .expect("Could not parse fish timers")
+            .into_iter()
     }
 }
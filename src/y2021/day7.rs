@@ -1,4 +1,4 @@
-use crate::Solution;
+use crate::{utils::parse, Solution};
 
 pub struct Day7;
 
@@ -121,7 +121,9 @@ impl Day7 {
     }
 
     /// Parse all crab positions, separated by commas
-    fn parse_data(data: &str) -> impl Iterator<Item = Position> + '_ {
-        data.trim_end().split(',').map(|s| s.parse().unwrap())
+    fn parse_data(data: &str) -> impl Iterator<Item = Position> {
+        parse::list(data, ",")
+            .expect("Could not parse crab positions")
+            .into_iter()
     }
 }
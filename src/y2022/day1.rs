@@ -2,7 +2,10 @@ use std::str::FromStr;
 
 use itertools::Itertools;
 
-use crate::Solution;
+use crate::{
+    utils::parse::{self, ParseError},
+    Solution,
+};
 
 pub struct Day1;
 
@@ -47,24 +50,11 @@ type Meal = Vec<FoodCalories>;
 struct Meals(Vec<Meal>);
 
 impl FromStr for Meals {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Meals(
-            // Split by blank lines to get groups
-            s.split("\n\n")
-                .map(|s| {
-                    // Get all of the group calories line per line
-                    s.split('\n')
-                        // Trim & remove potentially empty lines
-                        .map(|line| line.trim())
-                        .flat_map(|line| line.parse())
-                        .collect::<Meal>()
-                })
-                // Do not keep potentially empty groups
-                .filter(|v| !v.is_empty())
-                .collect(),
-        ))
+        // Each group of lines is a meal, with the calories of a food per line
+        parse::paragraphs(s, |meal| parse::lines(meal, parse::value)).map(Meals)
     }
 }
 
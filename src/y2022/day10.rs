@@ -1,7 +1,10 @@
 use std::fmt::Display;
 use std::str::FromStr;
 
-use crate::Solution;
+use crate::{
+    utils::{ocr, CheckedYX, Grid},
+    Solution,
+};
 
 pub struct Day10;
 
@@ -42,7 +45,8 @@ impl Solution for Day10 {
             tick += 1;
         }
 
-        crt.to_string()
+        // Fall back to the art if the letters cannot be read
+        ocr::recognize(&crt).unwrap_or_else(|| crt.to_string())
     }
 }
 
@@ -124,7 +128,7 @@ impl FromStr for Instruction {
 }
 
 struct Crt {
-    data: [[bool; Self::width() as _]; Self::height() as _],
+    data: Grid<bool>,
 }
 
 impl Crt {
@@ -139,38 +143,27 @@ impl Crt {
     fn update(&mut self, cpu: &Cpu, tick: u16) {
         let tick_y = (tick - 1) / Self::width() as u16;
         let tick_x = (tick - 1) % Self::width() as u16;
-        if tick_y < Self::height() as _ {
-            self.data[tick_y as usize][tick_x as usize] =
-                i16::abs_diff(tick_x as _, cpu.reg_x) <= 1;
+        if let Some(pos) = CheckedYX::new(&self.data, (tick_y as _, tick_x as _)) {
+            *self.data.get_mut(pos) = i16::abs_diff(tick_x as _, cpu.reg_x) <= 1;
         }
     }
 
     fn new() -> Self {
         Self {
-            data: [[false; Self::width() as _]; Self::height() as _],
+            data: Grid::new(Self::height() as _, Self::width() as _, false),
         }
     }
 }
 
+impl AsRef<Grid<bool>> for Crt {
+    fn as_ref(&self) -> &Grid<bool> {
+        &self.data
+    }
+}
+
 impl Display for Crt {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for y in 0..Self::height() {
-            for x in 0..Self::width() {
-                write!(
-                    f,
-                    "{}",
-                    if self.data[y as usize][x as usize] {
-                        '█'
-                    } else {
-                        ' '
-                    }
-                )?;
-            }
-            if y != Self::height() - 1 {
-                writeln!(f)?;
-            }
-        }
-        Ok(())
+        self.data.render(|&b| if b { '█' } else { ' ' }).fmt(f)
     }
 }
 
@@ -4,7 +4,13 @@ use std::str::FromStr;
 
 use itertools::Itertools;
 
-use crate::Solution;
+use crate::{
+    utils::{
+        math,
+        parse::{self, KeyValues, ParseError},
+    },
+    Solution,
+};
 
 pub struct Day11;
 
@@ -30,10 +36,8 @@ impl Solution for Day11 {
         let monkeys: Monkeys = parse1(data).map(RefCell::new).collect();
         let mut inspected = vec![0u64; monkeys.len()];
 
-        let convenient_modulo = monkeys
-            .iter()
-            .map(|monkey| monkey.borrow().div_by)
-            .reduce(|acc, e| acc * e);
+        // Every monkey test still gives the same result modulo any multiple of its divisor
+        let convenient_modulo = math::lcm_all(monkeys.iter().map(|monkey| monkey.borrow().div_by));
 
         for _round in 0..10000 {
             for (i, monkey) in monkeys.iter().enumerate() {
@@ -42,7 +46,7 @@ impl Solution for Day11 {
 
                 monkey
                     .borrow_mut()
-                    .inspect_items(&monkeys, convenient_modulo);
+                    .inspect_items(&monkeys, Some(convenient_modulo));
             }
         }
 
@@ -51,8 +55,10 @@ impl Solution for Day11 {
     }
 }
 
-fn parse1(data: &str) -> impl Iterator<Item = Monkey> + '_ {
-    data.split("\n\n").map(str::trim).flat_map(|s| s.parse())
+fn parse1(data: &str) -> impl Iterator<Item = Monkey> {
+    parse::paragraphs(data, str::parse)
+        .expect("Could not parse the monkeys")
+        .into_iter()
 }
 
 type Monkeys = Vec<RefCell<Monkey>>;
@@ -95,7 +101,7 @@ impl Monkey {
     }
 
     const fn test(&self, item: ItemWorry) -> bool {
-        item % self.div_by == 0
+        item.is_multiple_of(self.div_by)
     }
 
     fn operation(&self, item: ItemWorry) -> ItemWorry {
@@ -138,64 +144,39 @@ impl Display for Monkey {
 }
 
 impl FromStr for Monkey {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut lines = s.split('\n').map(str::trim);
-
-        // Monkey X:
-        lines.next();
+        let monkey = KeyValues::parse(s, ":")?;
 
         // Starting items: X, Y, Z
-        let starting_items = lines.next().ok_or(())?;
-        let starting_items = starting_items.strip_prefix("Starting items: ").ok_or(())?;
-        let starting_items: Vec<ItemWorry> =
-            starting_items.split(", ").flat_map(|s| s.parse()).collect();
+        let items = parse::list(monkey.raw("Starting items")?, ",")?;
 
         // Operation: new = old (+|*) (old|X)
-        let operation = lines.next().ok_or(())?;
-        let operation = operation.strip_prefix("Operation: new = old ").ok_or(())?;
-        let op: fn(ItemWorry, ItemWorry) -> ItemWorry = match operation.chars().next().unwrap() {
+        let (op, operand): (char, String) =
+            parse::pattern("new = old {op} {operand}", monkey.raw("Operation")?)?;
+        let op: fn(ItemWorry, ItemWorry) -> ItemWorry = match op {
             '+' => |old, v| old + v,
             '*' => |old, v| old * v,
-            _ => unreachable!(),
+            _ => {
+                return Err(ParseError::Mismatch {
+                    expected: "+ or *".to_owned(),
+                    found: op.to_string(),
+                })
+            }
         };
-        let op_val = match operation.split(' ').last().unwrap() {
+        let op_val = match operand.as_str() {
             "old" => None,
-            v => v.parse().ok(),
+            v => Some(parse::value(v)?),
         };
 
-        // Test: divisible by X
-        let test = lines.next().ok_or(())?;
-        let div_by = test
-            .strip_prefix("Test: divisible by ")
-            .ok_or(())?
-            .parse::<ItemWorry>()
-            .map_err(|_| ())?;
-
-        // If true: throw to monkey X
-        let if_true = lines.next().ok_or(())?;
-        let if_true_throw_to = if_true
-            .strip_prefix("If true: throw to monkey ")
-            .ok_or(())?
-            .parse::<MonkeyIdx>()
-            .map_err(|_| ())?;
-
-        // If false: throw to monkey X
-        let if_false = lines.next().ok_or(())?;
-        let if_false_throw_to = if_false
-            .strip_prefix("If false: throw to monkey ")
-            .ok_or(())?
-            .parse::<MonkeyIdx>()
-            .map_err(|_| ())?;
-
         Ok(Self {
-            items: starting_items,
+            items,
             op,
             op_val,
-            div_by,
-            if_true_throw_to,
-            if_false_throw_to,
+            div_by: monkey.get("Test", "divisible by ")?,
+            if_true_throw_to: monkey.get("If true", "throw to monkey ")?,
+            if_false_throw_to: monkey.get("If false", "throw to monkey ")?,
         })
     }
 }
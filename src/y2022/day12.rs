@@ -1,27 +1,26 @@
-use std::cmp::Reverse;
-use std::collections::BinaryHeap;
 use std::ops::Index;
 use std::str::FromStr;
 
-use itertools::Itertools;
-
-use crate::Solution;
+use crate::{
+    utils::{search, CheckedYX, Grid},
+    Solution,
+};
 
 pub struct Day12;
 
 impl Solution for Day12 {
     fn q1(&self, data: &str) -> String {
         let map: Map = data.parse().unwrap();
-        map.dijkstra1().to_string()
+        map.fewest_steps1().to_string()
     }
 
     fn q2(&self, data: &str) -> String {
         let map: Map = data.parse().unwrap();
-        map.dijkstra2().to_string()
+        map.fewest_steps2().to_string()
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 struct Position {
     x: u8,
     y: u8,
@@ -36,103 +35,41 @@ struct Map {
     end: Position,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct HeapElt {
-    pos: Position,
-    cost: u16,
-}
-
-impl PartialOrd for HeapElt {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-impl Ord for HeapElt {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.cost.cmp(&other.cost)
-    }
-}
-
 impl Map {
-    fn dijkstra1(&self) -> u16 {
-        let mut visited = vec![false; self.height * self.width];
-        let mut heap = BinaryHeap::new();
-
-        heap.push(Reverse(HeapElt {
-            pos: self.start,
-            cost: 0,
-        }));
-
-        while let Some(Reverse(elt)) = heap.pop() {
-            if elt.pos == self.end {
-                return elt.cost;
-            }
-
-            if visited[self.idx(elt.pos)] {
-                continue;
-            }
-
-            visited[self.idx(elt.pos)] = true;
-
-            [
-                self.left(elt.pos),
-                self.right(elt.pos),
-                self.up(elt.pos),
-                self.down(elt.pos),
-            ]
-            .into_iter()
-            .flatten()
-            .filter(|pos| !visited[self.idx(*pos)])
-            .filter(|pos| self[*pos] <= self[elt.pos] + 1)
-            .map(|pos| HeapElt {
-                pos,
-                cost: elt.cost + 1,
-            })
-            .for_each(|elt| heap.push(Reverse(elt)));
-        }
-
-        panic!("End not found!");
+    /// Fewest steps from the start to the end, climbing at most one level per step.
+    fn fewest_steps1(&self) -> u16 {
+        let neighbors = |&pos: &Position| {
+            self.neighbors(pos)
+                .filter(move |next| self[*next] <= self[pos] + 1)
+        };
+
+        search::bfs([self.start], neighbors, |&pos| pos == self.end)
+            .goal_cost()
+            .expect("End not found!") as _
     }
 
-    fn dijkstra2(&self) -> u16 {
-        let mut visited = vec![false; self.height * self.width];
-        let mut heap = BinaryHeap::new();
-
-        heap.push(Reverse(HeapElt {
-            pos: self.end,
-            cost: 0,
-        }));
-
-        while let Some(Reverse(elt)) = heap.pop() {
-            if self[elt.pos] == 0 {
-                return elt.cost;
-            }
-
-            if visited[self.idx(elt.pos)] {
-                continue;
-            }
-
-            visited[self.idx(elt.pos)] = true;
-
-            [
-                self.left(elt.pos),
-                self.right(elt.pos),
-                self.up(elt.pos),
-                self.down(elt.pos),
-            ]
-            .into_iter()
-            .flatten()
-            .filter(|pos| !visited[self.idx(*pos)])
-            .filter(|pos| self[*pos] + 1 >= self[elt.pos])
-            .map(|pos| HeapElt {
-                pos,
-                cost: elt.cost + 1,
-            })
-            .for_each(|elt| heap.push(Reverse(elt)));
-        }
+    /// Fewest steps from any lowest point to the end.
+    /// Search backwards from the end, so the first lowest point reached is the nearest.
+    fn fewest_steps2(&self) -> u16 {
+        let neighbors = |&pos: &Position| {
+            self.neighbors(pos)
+                .filter(move |next| self[*next] + 1 >= self[pos])
+        };
+
+        search::bfs([self.end], neighbors, |&pos| self[pos] == 0)
+            .goal_cost()
+            .expect("End not found!") as _
+    }
 
-        panic!("End not found!");
+    fn neighbors(&self, pos: Position) -> impl Iterator<Item = Position> {
+        [
+            self.left(pos),
+            self.right(pos),
+            self.up(pos),
+            self.down(pos),
+        ]
+        .into_iter()
+        .flatten()
     }
 
     fn idx(&self, pos: Position) -> usize {
@@ -196,52 +133,28 @@ impl FromStr for Map {
     type Err = ();
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let start = s
-            .lines()
-            .enumerate()
-            .filter_map(|(y, line)| {
-                line.chars()
-                    .find_position(|c| *c == 'S')
-                    .map(|(x, _)| Position {
-                        y: y as _,
-                        x: x as _,
-                    })
-            })
-            .next()
-            .ok_or(())?;
-        let end = s
-            .lines()
-            .enumerate()
-            .filter_map(|(y, line)| {
-                line.chars()
-                    .find_position(|c| *c == 'E')
-                    .map(|(x, _)| Position {
-                        y: y as _,
-                        x: x as _,
-                    })
-            })
-            .next()
-            .ok_or(())?;
-
-        let width = s.lines().next().ok_or(())?.len();
+        let (grid, [start, end]) = Grid::parse_with_markers(s.lines(), ['S', 'E'], |c| match c {
+            'a'..='z' => Some(c as u8 - b'a'),
+            'S' => Some(0),
+            'E' => Some(b'z' - b'a'),
+            _ => None,
+        })
+        .map_err(|_| ())?;
 
-        let map: Vec<u8> = s
-            .chars()
-            .filter_map(|c| match c {
-                'a'..='z' => Some(c as u8 - b'a'),
-                'S' => Some(0),
-                'E' => Some(b'z' - b'a'),
-                _ => None,
-            })
-            .collect();
+        let to_position = |pos: CheckedYX| {
+            let (y, x) = *pos;
+            Position {
+                y: y as _,
+                x: x as _,
+            }
+        };
 
-        let height = map.len() / width;
         Ok(Map {
-            map,
-            width,
-            height,
-            start,
-            end,
+            map: grid.data,
+            width: grid.width,
+            height: grid.height,
+            start: start.map(to_position).ok_or(())?,
+            end: end.map(to_position).ok_or(())?,
         })
     }
 }
@@ -1,9 +1,8 @@
 use std::collections::HashSet;
-use std::hash::Hash;
 use std::ops::RangeInclusive;
 use std::str::FromStr;
 
-use crate::Solution;
+use crate::{utils::geom::Vec2, Solution};
 
 pub struct Day14;
 
@@ -35,7 +34,7 @@ fn parse1(data: &str) -> impl Iterator<Item = Path> + '_ {
 
 struct Map {
     blocked: HashSet<Position>,
-    the_void_y: u16,
+    the_void_y: i16,
     part_two: bool,
     prev_path: Vec<Position>,
 }
@@ -59,10 +58,10 @@ impl Map {
             .into_iter()
             .flat_map(|path| path.segments)
             .for_each(|seg| match seg {
-                Segment::Hor { y, x } => x.map(|x| Position { y, x: x as _ }).for_each(|p| {
+                Segment::Hor { y, x } => x.map(|x| Position::new(x, y)).for_each(|p| {
                     blocked.insert(p);
                 }),
-                Segment::Ver { x, y } => y.map(|y| Position { y, x: x as _ }).for_each(|p| {
+                Segment::Ver { x, y } => y.map(|y| Position::new(x, y)).for_each(|p| {
                     blocked.insert(p);
                 }),
             });
@@ -76,29 +75,20 @@ impl Map {
     }
 
     fn fall_sand(&mut self) -> Option<Position> {
-        let mut pos = self.prev_path.pop().unwrap_or(Position { x: 500, y: 0 });
+        let mut pos = self.prev_path.pop().unwrap_or(Position::new(500, 0));
 
         while pos.y < self.the_void_y {
-            let down = Position {
-                x: pos.x,
-                y: pos.y + 1,
-            };
+            let down = pos + Position::new(0, 1);
             if !self.is_blocked(down) {
                 pos = down;
                 self.prev_path.push(pos);
             } else {
-                let down_left = Position {
-                    x: pos.x - 1,
-                    y: pos.y + 1,
-                };
+                let down_left = pos + Position::new(-1, 1);
                 if !self.is_blocked(down_left) {
                     pos = down_left;
                     self.prev_path.push(pos);
                 } else {
-                    let down_right = Position {
-                        x: pos.x + 1,
-                        y: pos.y + 1,
-                    };
+                    let down_right = pos + Position::new(1, 1);
                     if !self.is_blocked(down_right) {
                         pos = down_right;
                         self.prev_path.push(pos);
@@ -124,8 +114,8 @@ struct Path {
 
 #[derive(Debug)]
 enum Segment {
-    Hor { y: u16, x: RangeInclusive<u16> },
-    Ver { x: u16, y: RangeInclusive<u16> },
+    Hor { y: i16, x: RangeInclusive<i16> },
+    Ver { x: i16, y: RangeInclusive<i16> },
 }
 
 impl Segment {
@@ -134,11 +124,11 @@ impl Segment {
             let mut ys = [p1.y, p2.y];
             ys.sort();
             Some(Self::Ver {
-                x: p1.x as _,
+                x: p1.x,
                 y: ys[0]..=ys[1],
             })
         } else if p1.y == p2.y {
-            let mut xs = [p1.x as _, p2.x as _];
+            let mut xs = [p1.x, p2.x];
             xs.sort();
             Some(Self::Hor {
                 y: p1.y,
@@ -150,11 +140,7 @@ impl Segment {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Position {
-    x: i16,
-    y: u16,
-}
+type Position = Vec2<i16>;
 
 impl FromStr for Path {
     type Err = ();
@@ -169,18 +155,6 @@ impl FromStr for Path {
     }
 }
 
-impl FromStr for Position {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (a, b) = s.split_once(',').ok_or(())?;
-        Ok(Self {
-            x: a.parse().map_err(|_| ())?,
-            y: b.parse().map_err(|_| ())?,
-        })
-    }
-}
-
 #[cfg(test)]
 mod test {
     use crate::Solution;
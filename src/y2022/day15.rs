@@ -1,8 +1,16 @@
+use std::ops::Range;
 use std::str::FromStr;
 
 use itertools::Itertools;
 
-use crate::Solution;
+use crate::{
+    utils::{
+        geom::Vec2,
+        intervals::{self, IntervalSet},
+        parse::{self, ParseError},
+    },
+    Solution,
+};
 
 pub struct Day15;
 
@@ -13,22 +21,20 @@ impl Solution for Day15 {
         #[cfg(test)]
         const Y: i32 = 10;
 
-        let sensors = parse1(data);
-        let mut ranges = vec![];
-        for sensor in sensors {
+        let sensors = parse1(data).collect_vec();
+        let mut ranges = IntervalSet::new();
+        for sensor in &sensors {
             if let Some(cur_range) = sensor.vision_on_line(Y) {
-                RangeInclX::merge_insert(&mut ranges, cur_range);
-                if sensor.beacon.y == Y {
-                    RangeInclX::remove_one(&mut ranges, sensor.beacon.x);
-                }
+                ranges.insert(cur_range);
             }
         }
 
-        ranges
-            .into_iter()
-            .map(|r| (r.end - r.start) as u32 + 1)
-            .sum::<u32>()
-            .to_string()
+        // Beacons positions cannot be where there are no beacons
+        for sensor in sensors.iter().filter(|s| s.beacon.y == Y) {
+            ranges.remove_point(sensor.beacon.x);
+        }
+
+        ranges.len().to_string()
     }
 
     fn q2(&self, data: &str) -> String {
@@ -36,9 +42,10 @@ impl Solution for Day15 {
         const MAX: i32 = 4000000;
         #[cfg(test)]
         const MAX: i32 = 20;
+        const BOUNDS: Range<i32> = 0..MAX + 1;
 
         let sensors = parse1(data).collect_vec();
-        let mut ranges = vec![];
+        let mut ranges = IntervalSet::new();
 
         // Search from the middle to the end, then from the middle to the start
         let ys = ((MAX / 2)..(MAX + 1)).chain((0..(MAX / 2)).rev());
@@ -48,16 +55,12 @@ impl Solution for Day15 {
             sensors
                 .iter()
                 .flat_map(|sensor| sensor.vision_on_line(y))
-                .map(|r| RangeInclX {
-                    start: i32::max(0, r.start),
-                    end: i32::min(MAX, r.end),
-                })
-                .filter(|r| r.start <= r.end)
-                .for_each(|r| RangeInclX::merge_insert(&mut ranges, r));
+                .flat_map(|r| intervals::intersect(&r, &BOUNDS))
+                .for_each(|r| ranges.insert(r));
 
-            if ranges.len() != 1 {
-                debug_assert_eq!(2, ranges.len());
-                let x = ranges.iter().map(|r| r.end).min().unwrap() as u128 + 1;
+            if ranges.len() != BOUNDS.end - BOUNDS.start {
+                let gap = ranges.complement(BOUNDS).iter().next().unwrap();
+                let x = gap.start as u128;
 
                 return (x * 4000000 + y as u128).to_string();
             }
@@ -67,134 +70,55 @@ impl Solution for Day15 {
     }
 }
 
-fn parse1(data: &str) -> impl Iterator<Item = Sensor> + '_ {
-    data.lines().flat_map(|s| s.parse())
-}
-
-#[derive(Debug)]
-struct RangeInclX {
-    start: i32,
-    end: i32,
-}
-
-impl RangeInclX {
-    fn contains(&self, x: i32) -> bool {
-        self.start <= x && x <= self.end
-    }
-
-    /// Try merge both ranges if they intersect, else return None
-    fn try_merge(&self, other: &Self) -> Option<Self> {
-        if self.end < other.start || other.end < self.start {
-            // no intersection
-            None
-        } else {
-            // intersection
-            Some(Self {
-                start: i32::min(self.start, other.start),
-                end: i32::max(self.end, other.end),
-            })
-        }
-    }
-
-    /// Insert a range in the ranges vec, doing the appropriate mergings
-    /// so that no range intersects another
-    fn merge_insert(ranges: &mut Vec<Self>, me: Self) {
-        let mut super_me = me;
-        for i in (0..ranges.len()).rev() {
-            if let Some(merge) = super_me.try_merge(ranges.get(i).unwrap()) {
-                super_me = merge;
-                ranges.swap_remove(i);
-            }
-        }
-        ranges.push(super_me);
-    }
-
-    /// Remove one position from the ranges. This will do nothing if it not contained in the ranges,
-    /// trim one range by 1, or split one range in two
-    fn remove_one(ranges: &mut Vec<Self>, x: i32) {
-        if let Some((idx, found_range)) = ranges.iter_mut().find_position(|r| r.contains(x)) {
-            let x_is_start = found_range.start == x;
-            let x_is_end = found_range.end == x;
-            match (x_is_start, x_is_end) {
-                (true, true) => {
-                    ranges.swap_remove(idx);
-                }
-                (true, false) => found_range.start = x + 1,
-                (false, true) => found_range.end = x - 1,
-                (false, false) => {
-                    let end = found_range.end;
-                    found_range.end = x - 1;
-                    ranges.push(Self { start: x + 1, end });
-                }
-            }
-        }
-    }
+fn parse1(data: &str) -> impl Iterator<Item = Sensor> {
+    parse::lines(data, str::parse)
+        .expect("Could not parse the sensors")
+        .into_iter()
 }
 
 #[derive(Debug)]
 struct Sensor {
     pos: Position,
     beacon: Position,
-    dist: u32,
+    dist: i32,
     vision_y_from: i32,
     vision_y_to: i32,
 }
 
 impl Sensor {
     /// The sensor vision on the given line. There may be the sensor and/or its beacon inside it
-    fn vision_on_line(&self, y: i32) -> Option<RangeInclX> {
+    fn vision_on_line(&self, y: i32) -> Option<Range<i32>> {
         if y < self.vision_y_from || y > self.vision_y_to {
             None
         } else {
             let dist = self.pos.y.abs_diff(y);
-            let drest = self.dist as i32 - dist as i32;
-            Some(RangeInclX {
-                start: self.pos.x - drest,
-                end: self.pos.x + drest,
-            })
+            let drest = self.dist - dist as i32;
+            Some(self.pos.x - drest..self.pos.x + drest + 1)
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Position {
-    x: i32,
-    y: i32,
-}
-
-impl Position {
-    fn dist(&self, other: &Self) -> u32 {
-        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
-    }
-}
+type Position = Vec2<i32>;
 
 impl FromStr for Sensor {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s.trim().strip_prefix("Sensor at x=").ok_or(())?;
-        let (x, s) = s.split_once(", y=").ok_or(())?;
-        let (y, s) = s.split_once(": ").ok_or(())?;
-        let s = s.strip_prefix("closest beacon is at x=").ok_or(())?;
-        let (bx, s) = s.split_once(", y=").ok_or(())?;
-        let by = s;
-
-        let pos = Position {
-            x: x.parse().map_err(|_| ())?,
-            y: y.parse().map_err(|_| ())?,
-        };
-        let beacon = Position {
-            x: bx.parse().map_err(|_| ())?,
-            y: by.parse().map_err(|_| ())?,
-        };
-        let dist = pos.dist(&beacon);
+        let (x, y, bx, by) = parse::pattern(
+            "Sensor at x={x}, y={y}: closest beacon is at x={x}, y={y}",
+            s.trim(),
+        )?;
+
+        let pos = Position::new(x, y);
+        let beacon = Position::new(bx, by);
+        let dist = pos.manhattan(beacon);
 
         Ok(Self {
             pos,
             beacon,
             dist,
-            vision_y_from: pos.y - dist as i32,
-            vision_y_to: pos.y + dist as i32,
+            vision_y_from: pos.y - dist,
+            vision_y_to: pos.y + dist,
         })
     }
 }
@@ -1,7 +1,7 @@
 use itertools::Itertools;
 use rayon::prelude::*;
 
-use crate::Solution;
+use crate::{utils::bitset::BitSet, Solution};
 
 pub struct Day16;
 
@@ -68,35 +68,20 @@ impl FastPaths {
     }
 
     fn max_pressure1(&self, rem_time: u8) -> u64 {
-        let mut visited = vec![false; self.time_from_start_to_valves.len()];
         self.time_from_start_to_valves
             .iter()
             .enumerate()
-            .map(|(i, t1)| {
-                visited[i] = true;
-                let r = self.max_pressure12(i, rem_time - *t1 - 1, &mut visited);
-                visited[i] = false;
-                r
-            })
+            .map(|(i, t1)| self.max_pressure12(i, rem_time - *t1 - 1, BitSet::EMPTY.with(i)))
             .max()
             .unwrap()
     }
 
-    fn max_pressure12(&self, i: usize, rem_time: u8, visited: &mut [bool]) -> u64 {
+    fn max_pressure12(&self, i: usize, rem_time: u8, visited: BitSet) -> u64 {
         self.time_between_valves[i]
             .iter()
             .enumerate()
-            .filter(|(_, t)| rem_time > *t + 1)
-            .map(|(i, t)| {
-                if !visited[i] {
-                    visited[i] = true;
-                    let r = self.max_pressure12(i, rem_time - *t - 1, visited);
-                    visited[i] = false;
-                    r
-                } else {
-                    0
-                }
-            })
+            .filter(|(i, t)| rem_time > *t + 1 && !visited.contains(*i))
+            .map(|(i, t)| self.max_pressure12(i, rem_time - *t - 1, visited.with(i)))
             .max()
             .unwrap_or(0)
             + self.flow_rates[i] as u64 * rem_time as u64
@@ -110,14 +95,10 @@ impl FastPaths {
             .filter(|((i1, _), (i2, _))| i1 != i2)
             .par_bridge()
             .map(|((i1, t1), (i2, t2))| {
-                let mut visited = vec![false; self.time_from_start_to_valves.len()];
-                visited[i1] = true;
-                visited[i2] = true;
+                let visited = BitSet::EMPTY.with(i1).with(i2);
                 let rem_time1 = rem_time - *t1 - 1;
                 let rem_time2 = rem_time - *t2 - 1;
-                let r = self.max_pressure22(i1, i2, rem_time1, rem_time2, &mut visited);
-                visited[i1] = false;
-                visited[i2] = false;
+                let r = self.max_pressure22(i1, i2, rem_time1, rem_time2, visited);
                 r + (self.flow_rates[i1] as u64 * rem_time1 as u64)
                     + (self.flow_rates[i2] as u64 * rem_time2 as u64)
             })
@@ -131,21 +112,16 @@ impl FastPaths {
         i2: usize,
         rem_time1: u8,
         rem_time2: u8,
-        visited: &mut [bool],
+        visited: BitSet,
     ) -> u64 {
         let r1 = self.time_between_valves[i1]
             .iter()
             .enumerate()
-            .filter(|(_, t1)| rem_time1 > *t1 + 1)
+            .filter(|(i1, t1)| rem_time1 > *t1 + 1 && !visited.contains(*i1))
             .map(|(i1, t1)| {
-                if !visited[i1] {
-                    visited[i1] = true;
-                    let r = self.max_pressure22(i1, i2, rem_time1 - *t1 - 1, rem_time2, visited);
-                    visited[i1] = false;
-                    r + (self.flow_rates[i1] as u64 * (rem_time1 - *t1 - 1) as u64)
-                } else {
-                    0
-                }
+                let rem_time1 = rem_time1 - *t1 - 1;
+                let r = self.max_pressure22(i1, i2, rem_time1, rem_time2, visited.with(i1));
+                r + (self.flow_rates[i1] as u64 * rem_time1 as u64)
             })
             .max()
             .unwrap_or(0);
@@ -153,16 +129,11 @@ impl FastPaths {
         let r2 = self.time_between_valves[i2]
             .iter()
             .enumerate()
-            .filter(|(_, t2)| rem_time2 > *t2 + 1)
+            .filter(|(i2, t2)| rem_time2 > *t2 + 1 && !visited.contains(*i2))
             .map(|(i2, t2)| {
-                if !visited[i2] {
-                    visited[i2] = true;
-                    let r = self.max_pressure22(i1, i2, rem_time1, rem_time2 - *t2 - 1, visited);
-                    visited[i2] = false;
-                    r + (self.flow_rates[i2] as u64 * (rem_time2 - *t2 - 1) as u64)
-                } else {
-                    0
-                }
+                let rem_time2 = rem_time2 - *t2 - 1;
+                let r = self.max_pressure22(i1, i2, rem_time1, rem_time2, visited.with(i2));
+                r + (self.flow_rates[i2] as u64 * rem_time2 as u64)
             })
             .max()
             .unwrap_or(0);
@@ -172,13 +143,16 @@ impl FastPaths {
 }
 
 mod valve {
-    use std::collections::VecDeque;
     use std::fmt::Debug;
     use std::fmt::Display;
     use std::str::FromStr;
 
     use itertools::Itertools;
-    use regex::Regex;
+
+    use crate::utils::{
+        parse::{self, ParseError, Pattern},
+        search,
+    };
 
     #[derive(Debug)]
     pub(super) struct Map {
@@ -223,24 +197,11 @@ mod valve {
         }
 
         pub fn fastest_path_time(&self, from: Idx, to: Idx) -> u8 {
-            let mut visited = vec![false; self.len()];
-            let mut to_visit = VecDeque::new();
-            to_visit.push_back((0, from));
-            visited[from.get() as usize] = true;
-
-            while let Some((time, idx)) = to_visit.pop_front() {
-                if idx == to {
-                    return time;
-                }
+            let neighbors = |&idx: &Idx| self.get_tunnels(idx).iter().copied();
 
-                for neigh in self.get_tunnels(idx) {
-                    if !visited[neigh.get() as usize] {
-                        visited[neigh.get() as usize] = true;
-                        to_visit.push_back((time + 1, *neigh));
-                    }
-                }
-            }
-            panic!("No path between the valves");
+            search::bfs([from], neighbors, |&idx| idx == to)
+                .goal_cost()
+                .expect("No path between the valves") as _
         }
     }
 
@@ -259,7 +220,7 @@ mod valve {
                     writeln!(
                         f,
                         "tunnel leads to valve {}",
-                        self.get_name(*tunnels.get(0).unwrap())
+                        self.get_name(*tunnels.first().unwrap())
                     )?;
                 } else {
                     writeln!(
@@ -274,7 +235,7 @@ mod valve {
     }
 
     impl FromStr for Map {
-        type Err = ();
+        type Err = ParseError;
 
         fn from_str(s: &str) -> Result<Self, Self::Err> {
             let mut map = Self {
@@ -285,41 +246,35 @@ mod valve {
 
             let mut tunnels_names = vec![];
 
-            let re = Regex::new(
-                r"Valve ([A-Z]{2}) has flow rate=(\d+); tunnels? leads? to valves? ([A-Z]{2}(?:, [A-Z]{2})*)",
-            )
-            .unwrap();
-
-            for line in s.lines().map(str::trim).filter(|s| !s.is_empty()) {
-                let cap = re.captures(line).unwrap();
-                let name = cap.get(1).unwrap().as_str();
-                let flow_rate = cap.get(2).unwrap().as_str().parse().unwrap();
-                let tunnels = cap.get(3).unwrap().as_str().split(", ");
+            let pattern = Pattern::new("Valve {name} has flow rate={rate}; {tunnels}");
+            let valves = parse::lines(s, |line| pattern.parse::<(String, u8, String)>(line))?;
 
+            for (name, flow_rate, tunnels) in valves {
                 // Add to the map
-                map.add_valve(name.to_owned(), flow_rate);
+                map.add_valve(name, flow_rate);
 
-                tunnels_names.push(tunnels);
+                // Either "tunnels lead to valves X, Y" or "tunnel leads to valve X"
+                let tunnels = tunnels.trim_start_matches(|c: char| !c.is_ascii_uppercase());
+                tunnels_names.push(tunnels.split(", ").map(str::to_owned).collect_vec());
             }
 
             map.tunnels = tunnels_names
                 .into_iter()
-                .map(|names| names.flat_map(|name| map.idx_of_name(name)).collect_vec())
+                .map(|names| {
+                    names
+                        .iter()
+                        .flat_map(|name| map.idx_of_name(name))
+                        .collect_vec()
+                })
                 .collect_vec();
 
             Ok(map)
         }
     }
 
-    #[derive(Clone, Copy, PartialEq, Eq)]
+    #[derive(Clone, Copy, PartialEq, Eq, Hash)]
     pub(super) struct Idx(u8);
 
-    impl Idx {
-        pub fn get(self) -> u8 {
-            self.0
-        }
-    }
-
     impl Debug for Idx {
         fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
             Debug::fmt(&self.0, f)
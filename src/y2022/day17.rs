@@ -1,11 +1,13 @@
 use std::collections::HashSet;
 use std::fmt::Debug;
 use std::fmt::Display;
-use std::ops::Add;
 
 use itertools::Itertools;
 
-use crate::Solution;
+use crate::{
+    utils::{cycle::CycleFinder, flood, geom::Vec2, Grid},
+    Solution,
+};
 
 pub struct Day17;
 
@@ -29,113 +31,35 @@ impl Solution for Day17 {
     }
 }
 
+/// Drop `nb_rocks` rocks in the chamber and return the height of the tower.
+///
+/// As the rock shapes and jets loop, the tower ends up repeating itself:
+/// once the same state is reached twice, the final height is extrapolated.
 fn run(chamber: &mut Chamber, nb_rocks: u64, jets: &[HotJetDir]) -> u64 {
     const FIRST_ROCK_SHAPE: RockShape = RockShape::Hor;
 
     let mut ijet = 0;
     let mut rock_shape = FIRST_ROCK_SHAPE;
 
-    let mut first_repeat: Option<(u64, u64, u64)> = None;
+    // The tower height after each number of dropped rocks
+    let mut heights = vec![0];
+    let mut cycles = CycleFinder::new();
 
-    for irock in 0..nb_rocks {
-        let mut rock = Rock {
-            bl: chamber.new_falling_rock_start_pos(),
-            shape: rock_shape,
-        };
-
-        loop {
-            if let Some(res) = check_repeat(
-                (irock, ijet as _),
-                rock_shape,
-                &mut first_repeat,
-                chamber,
-                nb_rocks,
-                jets,
-                rock.bl,
-            ) {
-                return res;
-            }
-
-            let jet = jets.get(ijet).unwrap();
-            ijet = (ijet + 1) % jets.len();
-
-            // Jet push
-            let next_rock = Rock {
-                bl: jet.push_in_direction(rock.bl),
-                shape: rock.shape,
-            };
-            match jet {
-                HotJetDir::Left => {
-                    if !chamber.is_blocked_leftside(next_rock) {
-                        rock.bl = next_rock.bl;
-                    }
-                }
-                HotJetDir::Right => {
-                    if !chamber.is_blocked_rightside(next_rock) {
-                        rock.bl = next_rock.bl;
-                    }
-                }
-            };
-
-            // Gravity
-            let down = Rock {
-                bl: rock.bl.down(),
-                shape: rock.shape,
-            };
-            if chamber.is_blocked_downside(down) {
-                chamber.rest_rock(rock);
-                break;
-            } else {
-                rock = down;
-            }
+    for _ in 0..nb_rocks {
+        let state = (rock_shape, ijet, chamber.surface());
+        if let Some(cycle) = cycles.push(state) {
+            return cycle.extrapolate(&heights, nb_rocks);
         }
 
+        chamber.drop_rock(rock_shape, jets, &mut ijet);
+        heights.push(chamber.height as u64);
+
         rock_shape = rock_shape.next_shape();
     }
 
     chamber.height as _
 }
 
-fn check_repeat(
-    (irock, ijet): (u64, u64),
-    rock_shape: RockShape,
-    first_repeat: &mut Option<(u64, u64, u64)>,
-    chamber: &mut Chamber,
-    nb_rocks: u64,
-    jets: &[HotJetDir],
-    rock_bl: Position,
-) -> Option<u64> {
-    if irock != 0 && rock_shape == RockShape::Hor && ijet == 0 {
-        if let Some((ifirst, hfirst, rock_y_diff)) = first_repeat {
-            if *rock_y_diff == (rock_bl.y as u64 - chamber.height as u64) {
-                let idiff = irock - *ifirst;
-                let hdiff = chamber.height as u64 - *hfirst;
-
-                let nb_repeat = (nb_rocks - *ifirst) / idiff;
-                let irest = (nb_rocks - *ifirst) % idiff;
-
-                let before_height = chamber.height as u64;
-                let hrest = run(chamber, irest, jets) - before_height;
-
-                assert_eq!(nb_rocks, *ifirst + (nb_repeat * idiff) + irest);
-
-                // Absolutely no idea what is wrong in my code
-                // but this has already taken too much time
-                const CHEAT_OFFSET: u64 = 23;
-
-                return Some(*hfirst + (nb_repeat * hdiff) + hrest - CHEAT_OFFSET);
-            }
-        } else {
-            *first_repeat = Some((
-                irock,
-                chamber.height as _,
-                rock_bl.y as u64 - chamber.height as u64,
-            ));
-        }
-    }
-    None
-}
-
 fn parse1(data: &str) -> impl Iterator<Item = HotJetDir> + '_ {
     data.trim().chars().map(|c| match c {
         '<' => HotJetDir::Left,
@@ -181,6 +105,69 @@ impl Chamber {
             .any(|p| p.y < 0 || self.rocks.contains(&p))
     }
 
+    /// Let a new rock fall until it rests, pushed by the jets starting at the given index.
+    fn drop_rock(&mut self, shape: RockShape, jets: &[HotJetDir], ijet: &mut usize) {
+        let mut rock = Rock {
+            bl: self.new_falling_rock_start_pos(),
+            shape,
+        };
+
+        loop {
+            let jet = jets[*ijet];
+            *ijet = (*ijet + 1) % jets.len();
+
+            // Jet push
+            let next_rock = Rock {
+                bl: jet.push_in_direction(rock.bl),
+                shape: rock.shape,
+            };
+            let blocked = match jet {
+                HotJetDir::Left => self.is_blocked_leftside(next_rock),
+                HotJetDir::Right => self.is_blocked_rightside(next_rock),
+            };
+            if !blocked {
+                rock = next_rock;
+            }
+
+            // Gravity
+            let down = Rock {
+                bl: rock.bl + Position::new(0, -1),
+                shape: rock.shape,
+            };
+            if self.is_blocked_downside(down) {
+                self.rest_rock(rock);
+                return;
+            }
+            rock = down;
+        }
+    }
+
+    /// A fingerprint of the top of the tower: the empty cells that falling rocks can
+    /// still reach, as (depth below the top, x), sorted.
+    ///
+    /// Rocks only move left, right and down, so the cells they can reach are found
+    /// by a flood fill from the top. Anything below them cannot change how the next
+    /// rocks fall, so states with the same surface evolve the same way.
+    fn surface(&self) -> Vec<(i64, i64)> {
+        let top = self.height as i64;
+        let starts = (0..self.width as i64).map(|x| Position { y: top, x });
+        let neighbors =
+            |&p: &Position| [(-1, 0), (1, 0), (0, -1)].map(|(dx, dy)| p + Position::new(dx, dy));
+        let is_inside = |p: &Position| {
+            (0..self.width as i64).contains(&p.x)
+                && (0..=top).contains(&p.y)
+                && !self.rocks.contains(p)
+        };
+
+        let mut cells = flood::flood_fill(starts, neighbors, is_inside)
+            .nodes
+            .into_iter()
+            .map(|p| (top - p.y, p.x))
+            .collect::<Vec<_>>();
+        cells.sort_unstable();
+        cells
+    }
+
     fn rest_rock(&mut self, rock: Rock) {
         rock.positions().for_each(|p| {
             self.rocks.insert(p);
@@ -192,24 +179,25 @@ impl Chamber {
 }
 
 impl Display for Chamber {
+    /// Draw the chamber between its walls, from the floor up.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "+-------+")?;
+        writeln!(f, "+{}+", "-".repeat(self.width))?;
+        if self.height == 0 {
+            return Ok(());
+        }
+
+        let mut grid = Grid::new(self.height, self.width + 2, ' ');
         for y in 0..self.height {
-            write!(f, "|")?;
-            for x in 0..7 {
-                write!(
-                    f,
-                    "{}",
-                    if self.rocks.contains(&Position { y: y as _, x }) {
-                        '█'
-                    } else {
-                        ' '
-                    }
-                )?;
-            }
-            writeln!(f, "|")?;
+            let row = grid.row_mut(y);
+            row[0] = '|';
+            row[self.width + 1] = '|';
         }
-        Ok(())
+        for p in &self.rocks {
+            grid.row_mut(p.y as usize)[p.x as usize + 1] = '█';
+        }
+
+        let render = grid.render(|&c| c);
+        writeln!(f, "{render}")
     }
 }
 
@@ -220,10 +208,10 @@ enum HotJetDir {
 }
 
 impl HotJetDir {
-    const fn push_in_direction(self, pos: Position) -> Position {
+    fn push_in_direction(self, pos: Position) -> Position {
         match self {
-            HotJetDir::Left => pos.left(),
-            HotJetDir::Right => pos.right(),
+            HotJetDir::Left => pos + Position::new(-1, 0),
+            HotJetDir::Right => pos + Position::new(1, 0),
         }
     }
 }
@@ -276,7 +264,7 @@ impl Display for Rock {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum RockShape {
     Hor,
     Cross,
@@ -419,51 +407,14 @@ impl Display for RockShape {
 
 type Offset = Position;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Position {
-    y: i64,
-    x: i64,
-}
-
-impl Position {
-    const fn left(self) -> Self {
-        Position {
-            y: self.y,
-            x: self.x - 1,
-        }
-    }
-
-    const fn right(self) -> Self {
-        Position {
-            y: self.y,
-            x: self.x + 1,
-        }
-    }
-
-    const fn down(self) -> Self {
-        Position {
-            y: self.y - 1,
-            x: self.x,
-        }
-    }
-}
-
-impl Add for Position {
-    type Output = Self;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Self::Output {
-            y: self.y + rhs.y,
-            x: self.x + rhs.x,
-        }
-    }
-}
+/// A position in the chamber, with the Y axis pointing up from the floor.
+type Position = Vec2<i64>;
 
 #[cfg(test)]
 mod test {
     use crate::Solution;
 
-    use super::Day17;
+    use super::{parse1, Chamber, Day17, RockShape};
 
     #[test]
     fn q1() {
@@ -481,4 +432,35 @@ mod test {
             day.q2(">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>")
         );
     }
+
+    #[test]
+    fn surface() {
+        let jets = parse1(">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>").collect::<Vec<_>>();
+        let mut chamber = Chamber::new(7);
+        assert_eq!(
+            (0..7).map(|x| (0, x)).collect::<Vec<_>>(),
+            chamber.surface()
+        );
+
+        // The first rock rests on the floor, leaving reachable cells on both sides
+        chamber.drop_rock(RockShape::Hor, &jets, &mut 0);
+        let mut expected = (0..7).map(|x| (0, x)).collect::<Vec<_>>();
+        expected.extend([(1, 0), (1, 1), (1, 6)]);
+        assert_eq!(expected, chamber.surface());
+    }
+
+    #[test]
+    fn display() {
+        let jets = parse1(">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>").collect::<Vec<_>>();
+        let mut chamber = Chamber::new(7);
+        assert_eq!("+-------+\n", chamber.to_string());
+
+        let mut ijet = 0;
+        chamber.drop_rock(RockShape::Hor, &jets, &mut ijet);
+        chamber.drop_rock(RockShape::Hor.next_shape(), &jets, &mut ijet);
+        assert_eq!(
+            "+-------+\n|  ████ |\n|   █   |\n|  ███  |\n|   █   |\n",
+            chamber.to_string()
+        );
+    }
 }
@@ -1,7 +1,9 @@
 use std::collections::HashSet;
-use std::str::FromStr;
 
-use crate::Solution;
+use crate::{
+    utils::{flood, geom::Vec3},
+    Solution,
+};
 
 pub struct Day18;
 
@@ -11,17 +13,9 @@ impl Solution for Day18 {
         droplets
             .iter()
             .map(|pos| {
-                [
-                    pos.add_x(1),
-                    pos.add_x(-1),
-                    pos.add_y(1),
-                    pos.add_y(-1),
-                    pos.add_z(1),
-                    pos.add_z(-1),
-                ]
-                .into_iter()
-                .filter(|pos| !droplets.contains(pos))
-                .count()
+                pos.neighbors6()
+                    .filter(|pos| !droplets.contains(pos))
+                    .count()
             })
             .sum::<usize>()
             .to_string()
@@ -37,101 +31,21 @@ fn parse1(data: &str) -> impl Iterator<Item = Pos3> + '_ {
     data.lines().flat_map(|s| s.parse())
 }
 
+/// Count the droplet faces reachable from the outside,
+/// by filling the air around the droplets.
 fn count_outside(droplets: &HashSet<Pos3>) -> usize {
-    const START_POS: Pos3 = Pos3 {
-        x: -1,
-        y: -1,
-        z: -1,
-    };
-    const MIN: i8 = -1;
-    const MAX: i8 = 20;
-
-    let mut visited = HashSet::new();
-    let mut to_visit = vec![START_POS];
-    visited.insert(START_POS);
-
-    let mut count = 0;
-    let mut visited_here = Vec::with_capacity(6);
-    while let Some(pos) = to_visit.pop() {
-        let neighs = [
-            pos.add_x(1),
-            pos.add_x(-1),
-            pos.add_y(1),
-            pos.add_y(-1),
-            pos.add_z(1),
-            pos.add_z(-1),
-        ]
-        .into_iter()
-        .filter(|p| p.in_bounds(MIN, MAX))
-        .filter(|p| !visited.contains(p));
-
-        for neigh in neighs {
-            if droplets.contains(&neigh) {
-                count += 1;
-            } else {
-                visited_here.push(neigh);
-                to_visit.push(neigh);
-            }
-        }
-
-        visited.extend(visited_here.iter());
-        visited_here.clear();
-    }
-    count
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Pos3 {
-    x: i8,
-    y: i8,
-    z: i8,
-}
-
-impl Pos3 {
-    const fn add_x(self, dx: i8) -> Self {
-        Self {
-            x: self.x + dx,
-            ..self
-        }
-    }
+    const MIN: Pos3 = Pos3::new(-1, -1, -1);
+    const MAX: Pos3 = Pos3::new(20, 20, 20);
+    const START_POS: Pos3 = MIN;
 
-    const fn add_y(self, dy: i8) -> Self {
-        Self {
-            y: self.y + dy,
-            ..self
-        }
-    }
-
-    const fn add_z(self, dz: i8) -> Self {
-        Self {
-            z: self.z + dz,
-            ..self
-        }
-    }
+    let neighbors = |pos: &Pos3| pos.neighbors6().filter(|p| p.in_bounds(MIN, MAX));
+    let air = flood::flood_fill([START_POS], neighbors, |pos| !droplets.contains(pos));
 
-    const fn in_bounds(self, min: i8, max: i8) -> bool {
-        self.x >= min
-            && self.x <= max
-            && self.y >= min
-            && self.y <= max
-            && self.z >= min
-            && self.z <= max
-    }
+    // Every edge between the air and a droplet is a droplet face
+    air.boundary.len()
 }
 
-impl FromStr for Pos3 {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (x, s) = s.trim().split_once(',').ok_or(())?;
-        let (y, z) = s.split_once(',').ok_or(())?;
-        Ok(Self {
-            x: x.parse().map_err(|_| ())?,
-            y: y.parse().map_err(|_| ())?,
-            z: z.parse().map_err(|_| ())?,
-        })
-    }
-}
+type Pos3 = Vec3<i8>;
 
 #[cfg(test)]
 mod test {
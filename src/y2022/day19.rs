@@ -1,34 +1,196 @@
-use crate::Solution;
+use std::str::FromStr;
+
+use rayon::prelude::*;
+
+use crate::{
+    utils::parse::{self, ParseError},
+    Solution,
+};
 
 pub struct Day19;
 
 impl Solution for Day19 {
-    fn q1(&self, _data: &str) -> String {
-        todo!()
+    fn q1(&self, data: &str) -> String {
+        const MINUTES: u32 = 24;
+
+        let blueprints = parse_data(data).unwrap();
+        blueprints
+            .par_iter()
+            .map(|blueprint| blueprint.id * blueprint.max_geodes(MINUTES))
+            .sum::<u32>()
+            .to_string()
     }
 
-    fn q2(&self, _data: &str) -> String {
-        todo!()
+    fn q2(&self, data: &str) -> String {
+        const MINUTES: u32 = 32;
+        const BLUEPRINTS: usize = 3;
+
+        let blueprints = parse_data(data).unwrap();
+        blueprints
+            .par_iter()
+            .take(BLUEPRINTS)
+            .map(|blueprint| blueprint.max_geodes(MINUTES))
+            .product::<u32>()
+            .to_string()
     }
 }
 
-// #[cfg(test)]
-// mod test {
-//     use crate::Solution;
+fn parse_data(data: &str) -> Result<Vec<Blueprint>, ParseError> {
+    parse::lines(data, str::parse)
+}
+
+/// The resources, which are also the kinds of robots collecting them.
+const ORE: usize = 0;
+const CLAY: usize = 1;
+const OBSIDIAN: usize = 2;
+const GEODE: usize = 3;
 
-//     use super::Day19;
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Blueprint {
+    id: u32,
+    /// The ore, clay and obsidian costs of each kind of robot
+    costs: [[u32; 3]; 4],
+}
 
-//     #[test]
-//     fn q1() {
-//         let day = Day19 {};
+/// The robots and resources at some point in time.
+#[derive(Debug, Clone, Copy)]
+struct State {
+    minutes_left: u32,
+    robots: [u32; 4],
+    resources: [u32; 4],
+}
 
-//         assert_eq!("", day.q1(""));
-//     }
+impl Blueprint {
+    /// The largest number of geodes that can be opened in the given time,
+    /// starting with a single ore robot.
+    fn max_geodes(&self, minutes: u32) -> u32 {
+        // A robot can only be built each minute, so there is no need to collect
+        // more of a resource per minute than the most any robot costs
+        let mut max_robots = [u32::MAX; 4];
+        for (resource, max) in max_robots.iter_mut().take(3).enumerate() {
+            *max = self.costs.iter().map(|cost| cost[resource]).max().unwrap();
+        }
+
+        let start = State {
+            minutes_left: minutes,
+            robots: [1, 0, 0, 0],
+            resources: [0; 4],
+        };
+        let mut best = 0;
+        self.search(start, &max_robots, &mut best);
+        best
+    }
+
+    /// Depth-first search over the next robot to build, skipping the minutes spent
+    /// waiting for its resources. Update `best` with the geodes of the best state found.
+    fn search(&self, state: State, max_robots: &[u32; 4], best: &mut u32) {
+        // The geodes opened if no more robots are built
+        let t = state.minutes_left;
+        let idle = state.resources[GEODE] + state.robots[GEODE] * t;
+        *best = (*best).max(idle);
+
+        // Even building a geode robot every minute left cannot beat the best
+        if idle + t * t.saturating_sub(1) / 2 <= *best {
+            return;
+        }
+
+        // Try geode robots first, to find good states early
+        for robot in (ORE..=GEODE).rev() {
+            if state.robots[robot] >= max_robots[robot] {
+                continue;
+            }
+
+            // The minutes to wait to collect enough resources for the robot
+            let wait = (ORE..=OBSIDIAN).try_fold(0, |wait, resource| {
+                let (cost, stock) = (self.costs[robot][resource], state.resources[resource]);
+                match state.robots[resource] {
+                    _ if cost <= stock => Some(wait),
+                    0 => None,
+                    n => Some(wait.max((cost - stock).div_ceil(n))),
+                }
+            });
+
+            // The robot must be built in time to collect something
+            let Some(wait) = wait.filter(|wait| wait + 1 < t) else {
+                continue;
+            };
+
+            let mut next = state;
+            next.minutes_left -= wait + 1;
+            for resource in ORE..=GEODE {
+                next.resources[resource] += state.robots[resource] * (wait + 1);
+            }
+            for resource in ORE..=OBSIDIAN {
+                next.resources[resource] -= self.costs[robot][resource];
+            }
+            next.robots[robot] += 1;
+
+            self.search(next, max_robots, best);
+        }
+    }
+}
 
-//     #[test]
-//     fn q2() {
-//         let day = Day19 {};
+impl FromStr for Blueprint {
+    type Err = ParseError;
 
-//         assert_eq!("", day.q2(""));
-//     }
-// }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const PATTERN: &str = "Blueprint {id}: \
+            Each ore robot costs {ore} ore. \
+            Each clay robot costs {ore} ore. \
+            Each obsidian robot costs {ore} ore and {clay} clay. \
+            Each geode robot costs {ore} ore and {obsidian} obsidian.";
+
+        let (id, ore, clay, obsidian_ore, obsidian_clay, geode_ore, geode_obsidian) =
+            parse::pattern(PATTERN, s)?;
+
+        let mut costs = [[0; 3]; 4];
+        costs[ORE][ORE] = ore;
+        costs[CLAY][ORE] = clay;
+        costs[OBSIDIAN][ORE] = obsidian_ore;
+        costs[OBSIDIAN][CLAY] = obsidian_clay;
+        costs[GEODE][ORE] = geode_ore;
+        costs[GEODE][OBSIDIAN] = geode_obsidian;
+
+        Ok(Self { id, costs })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Solution;
+
+    use super::{parse_data, Blueprint, Day19};
+
+    #[test]
+    fn q1() {
+        let day = Day19 {};
+
+        assert_eq!("33", day.q1(DATA1));
+    }
+
+    #[test]
+    fn q2() {
+        let day = Day19 {};
+
+        assert_eq!((56 * 62).to_string(), day.q2(DATA1));
+    }
+
+    #[test]
+    fn parse() {
+        let blueprints = parse_data(DATA1).unwrap();
+        assert_eq!(
+            Blueprint {
+                id: 2,
+                costs: [[2, 0, 0], [3, 0, 0], [3, 8, 0], [3, 0, 12]]
+            },
+            blueprints[1]
+        );
+        assert_eq!(9, blueprints[0].max_geodes(24));
+        assert_eq!(12, blueprints[1].max_geodes(24));
+
+        assert!(parse_data("Blueprint 1: Each ore robot costs 4 ore.").is_err());
+    }
+
+    const DATA1: &str = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.
+Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsidian robot costs 3 ore and 8 clay. Each geode robot costs 3 ore and 12 obsidian.";
+}
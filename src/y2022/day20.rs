@@ -1,34 +1,215 @@
-use crate::Solution;
+use crate::{utils::parse, Solution};
 
 pub struct Day20;
 
 impl Solution for Day20 {
-    fn q1(&self, _data: &str) -> String {
-        todo!()
+    fn q1(&self, data: &str) -> String {
+        let numbers = parse::lines(data, parse::value).unwrap();
+        let mixed = mix(&numbers, 1);
+        grove_coordinates(&numbers, &mixed).to_string()
     }
 
-    fn q2(&self, _data: &str) -> String {
-        todo!()
+    fn q2(&self, data: &str) -> String {
+        const DECRYPTION_KEY: i64 = 811589153;
+        const ROUNDS: usize = 10;
+
+        let numbers = parse::lines(data, parse::value::<i64>).unwrap();
+        let numbers = numbers
+            .iter()
+            .map(|n| n * DECRYPTION_KEY)
+            .collect::<Vec<_>>();
+        let mixed = mix(&numbers, ROUNDS);
+        grove_coordinates(&numbers, &mixed).to_string()
+    }
+}
+
+/// The sum of the numbers 1000, 2000 and 3000 positions after the 0,
+/// given the ids of the numbers in their mixed order.
+fn grove_coordinates(numbers: &[i64], mixed: &[usize]) -> i64 {
+    let zero = mixed
+        .iter()
+        .position(|&id| numbers[id] == 0)
+        .expect("There is no 0");
+    [1000, 2000, 3000]
+        .iter()
+        .map(|offset| numbers[mixed[(zero + offset) % mixed.len()]])
+        .sum()
+}
+
+/// Move each number forward or backward in the circular list by its value, in their
+/// original order, for the given number of rounds.
+///
+/// Numbers are identified by their original index, as the same value may appear multiple times.
+/// Return the ids in their mixed order, starting anywhere.
+fn mix(numbers: &[i64], rounds: usize) -> Vec<usize> {
+    let n = numbers.len();
+    let mut list = BlockList::new(n);
+
+    // With only one number, it cannot move
+    if n > 1 {
+        for _ in 0..rounds {
+            for (id, &number) in numbers.iter().enumerate() {
+                // Once removed, the other numbers form a loop of n - 1 numbers
+                let position = list.remove(id);
+                let position = (position as i64 + number).rem_euclid(n as i64 - 1);
+                list.insert(position as usize, id);
+            }
+        }
+    }
+
+    list.iter().collect()
+}
+
+/// An ordered list of the ids `0..n`, split into blocks of about √n ids,
+/// to find, remove and insert ids by position in O(√n).
+struct BlockList {
+    blocks: Vec<Vec<usize>>,
+    /// The block containing each id
+    block_of: Vec<usize>,
+    block_size: usize,
+}
+
+impl BlockList {
+    /// The list of the ids `0..n`, in order.
+    fn new(n: usize) -> Self {
+        let block_size = (n as f64).sqrt().ceil().max(1.) as usize;
+        let mut list = Self {
+            blocks: vec![(0..n).collect()],
+            block_of: vec![0; n],
+            block_size,
+        };
+        list.rebuild();
+        list
+    }
+
+    /// Split the ids into blocks of the same size again.
+    fn rebuild(&mut self) {
+        let ids = self.iter().collect::<Vec<_>>();
+        self.blocks = ids.chunks(self.block_size).map(<[usize]>::to_vec).collect();
+        if self.blocks.is_empty() {
+            self.blocks.push(vec![]);
+        }
+
+        for (b, block) in self.blocks.iter().enumerate() {
+            for &id in block {
+                self.block_of[id] = b;
+            }
+        }
+    }
+
+    /// Remove an id from the list, and return the position it was at.
+    fn remove(&mut self, id: usize) -> usize {
+        let b = self.block_of[id];
+        let before = self.blocks[..b].iter().map(Vec::len).sum::<usize>();
+        let i = self.blocks[b]
+            .iter()
+            .position(|&other| other == id)
+            .unwrap();
+        self.blocks[b].remove(i);
+        before + i
+    }
+
+    /// Insert an id so that it is at the given position, at most the length of the list.
+    fn insert(&mut self, mut position: usize, id: usize) {
+        let last = self.blocks.len() - 1;
+        let mut b = 0;
+        while b < last && position > self.blocks[b].len() {
+            position -= self.blocks[b].len();
+            b += 1;
+        }
+
+        self.blocks[b].insert(position, id);
+        self.block_of[id] = b;
+
+        // Keep the blocks small for the next operations
+        if self.blocks[b].len() > 2 * self.block_size {
+            self.rebuild();
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.blocks.iter().flatten().copied()
     }
 }
 
-// #[cfg(test)]
-// mod test {
-//     use crate::Solution;
+#[cfg(test)]
+mod test {
+    use crate::{
+        utils::test_rng::{Rng, SEED},
+        Solution,
+    };
 
-//     use super::Day20;
+    use super::{mix, Day20};
 
-//     #[test]
-//     fn q1() {
-//         let day = Day20 {};
+    #[test]
+    fn q1() {
+        let day = Day20 {};
 
-//         assert_eq!("", day.q1(""));
-//     }
+        assert_eq!("3", day.q1(DATA1));
+    }
 
-//     #[test]
-//     fn q2() {
-//         let day = Day20 {};
+    #[test]
+    fn q2() {
+        let day = Day20 {};
 
-//         assert_eq!("", day.q2(""));
-//     }
-// }
+        assert_eq!("1623178306", day.q2(DATA1));
+    }
+
+    #[test]
+    fn against_naive() {
+        /// Mix by moving the numbers in a vector, in O(n²)
+        fn naive(numbers: &[i64], rounds: usize) -> Vec<usize> {
+            let mut list = (0..numbers.len()).collect::<Vec<_>>();
+            for _ in 0..rounds {
+                for (id, &number) in numbers.iter().enumerate() {
+                    let position = list.iter().position(|&other| other == id).unwrap();
+                    list.remove(position);
+                    let position = (position as i64 + number).rem_euclid(list.len() as i64);
+                    list.insert(position as usize, id);
+                }
+            }
+            list
+        }
+
+        /// The ids as seen from the first number, to compare circular lists
+        fn from_first(mixed: Vec<usize>) -> Vec<usize> {
+            let start = mixed.iter().position(|&id| id == 0).unwrap();
+            mixed[start..]
+                .iter()
+                .chain(&mixed[..start])
+                .copied()
+                .collect()
+        }
+
+        let mut rng = Rng::new(SEED);
+        for _ in 0..50 {
+            let n = rng.range(2..300) as usize;
+            // Repeated values, including 0s and numbers which go around the loop back to their place
+            let numbers = (0..n)
+                .map(|_| match rng.range(0..4) {
+                    0 => 0,
+                    1 => rng.int(-3..4) * (n as i64 - 1),
+                    2 => rng.int(-5..5),
+                    _ => rng.int(-1000..1000) * 811589153,
+                })
+                .collect::<Vec<_>>();
+
+            let rounds = rng.range(1..4) as usize;
+            assert_eq!(
+                from_first(naive(&numbers, rounds)),
+                from_first(mix(&numbers, rounds))
+            );
+        }
+
+        assert_eq!(vec![0], mix(&[5], 3));
+        assert!(mix(&[], 1).is_empty());
+    }
+
+    const DATA1: &str = "1
+2
+-3
+3
+-2
+0
+4";
+}
@@ -1,34 +1,316 @@
-use crate::Solution;
+use std::{collections::HashMap, fmt::Display};
+
+use crate::{
+    utils::parse::{self, ParseError},
+    Solution,
+};
 
 pub struct Day21;
 
 impl Solution for Day21 {
-    fn q1(&self, _data: &str) -> String {
-        todo!()
+    fn q1(&self, data: &str) -> String {
+        let monkeys = Monkeys::parse(data).unwrap();
+        match monkeys.expression(ROOT, None) {
+            Expr::Number(n) => n.to_string(),
+            expr => panic!("root could not be evaluated: {expr}"),
+        }
+    }
+
+    fn q2(&self, data: &str) -> String {
+        let monkeys = Monkeys::parse(data).unwrap();
+        let equation = monkeys.equation().unwrap();
+        equation
+            .solve()
+            .expect("No integer solves the equation")
+            .to_string()
     }
+}
+
+const ROOT: &str = "root";
+const HUMAN: &str = "humn";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+/// Why an operation has no integer result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OpError {
+    Overflow,
+    DivisionByZero,
+    InexactDivision,
+}
 
-    fn q2(&self, _data: &str) -> String {
-        todo!()
+impl Display for OpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Overflow => write!(f, "the result overflows"),
+            Self::DivisionByZero => write!(f, "the divisor is 0"),
+            Self::InexactDivision => write!(f, "the division is not exact"),
+        }
     }
 }
 
-// #[cfg(test)]
-// mod test {
-//     use crate::Solution;
+impl Op {
+    /// Apply the operation, which must give an integer.
+    fn apply(self, a: i64, b: i64) -> Result<i64, OpError> {
+        match self {
+            Op::Add => a.checked_add(b).ok_or(OpError::Overflow),
+            Op::Sub => a.checked_sub(b).ok_or(OpError::Overflow),
+            Op::Mul => a.checked_mul(b).ok_or(OpError::Overflow),
+            Op::Div if b == 0 => Err(OpError::DivisionByZero),
+            // The remainder only overflows for i64::MIN / -1
+            Op::Div => match a.checked_rem(b).ok_or(OpError::Overflow)? {
+                0 => Ok(a / b),
+                _ => Err(OpError::InexactDivision),
+            },
+        }
+    }
+
+    fn symbol(self) -> char {
+        match self {
+            Op::Add => '+',
+            Op::Sub => '-',
+            Op::Mul => '*',
+            Op::Div => '/',
+        }
+    }
+}
 
-//     use super::Day21;
+impl TryFrom<&str> for Op {
+    type Error = ParseError;
 
-//     #[test]
-//     fn q1() {
-//         let day = Day21 {};
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "+" => Ok(Op::Add),
+            "-" => Ok(Op::Sub),
+            "*" => Ok(Op::Mul),
+            "/" => Ok(Op::Div),
+            _ => Err(ParseError::InvalidValue {
+                value: s.to_owned(),
+                ty: "Op",
+            }),
+        }
+    }
+}
 
-//         assert_eq!("", day.q1(""));
-//     }
+/// What a monkey yells
+#[derive(Debug, Clone)]
+enum Job {
+    Number(i64),
+    Operation(String, Op, String),
+}
 
-//     #[test]
-//     fn q2() {
-//         let day = Day21 {};
+/// An expression of the monkeys, where the numbers have been computed
+/// as far as possible without the unknown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+    Number(i64),
+    Unknown,
+    Operation(Box<Expr>, Op, Box<Expr>),
+}
+
+impl Expr {
+    /// The value of the unknown making the expression equal to `target`, or None if no integer does.
+    ///
+    /// Only one side of each operation depends on the unknown,
+    /// so each operation can be inverted on the path to the unknown.
+    fn solve_for(&self, target: i64) -> Option<i64> {
+        match self {
+            Expr::Unknown => Some(target),
+            // The unknown is not part of the expression
+            Expr::Number(_) => None,
+            Expr::Operation(a, op, b) => match (a.as_ref(), b.as_ref()) {
+                // a op x = target
+                (&Expr::Number(a), x) => {
+                    let x_target = match op {
+                        Op::Add => Op::Sub.apply(target, a),
+                        Op::Sub => Op::Sub.apply(a, target),
+                        Op::Mul => Op::Div.apply(target, a),
+                        Op::Div => Op::Div.apply(a, target),
+                    };
+                    x.solve_for(x_target.ok()?)
+                }
+                // x op b = target
+                (x, &Expr::Number(b)) => {
+                    let x_target = match op {
+                        Op::Add => Op::Sub.apply(target, b),
+                        Op::Sub => Op::Add.apply(target, b),
+                        Op::Mul => Op::Div.apply(target, b),
+                        Op::Div => Op::Mul.apply(target, b),
+                    };
+                    x.solve_for(x_target.ok()?)
+                }
+                _ => panic!("The unknown appears on both sides of {self}"),
+            },
+        }
+    }
+}
+
+impl Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expr::Number(n) => write!(f, "{n}"),
+            Expr::Unknown => write!(f, "{HUMAN}"),
+            Expr::Operation(a, op, b) => write!(f, "({a} {} {b})", op.symbol()),
+        }
+    }
+}
+
+/// An equation between two expressions, where only one contains the unknown.
+#[derive(Debug)]
+struct Equation {
+    unknown: Expr,
+    value: i64,
+}
+
+impl Equation {
+    fn solve(&self) -> Option<i64> {
+        self.unknown.solve_for(self.value)
+    }
+}
+
+impl Display for Equation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} = {}", self.unknown, self.value)
+    }
+}
 
-//         assert_eq!("", day.q2(""));
-//     }
-// }
+struct Monkeys {
+    jobs: HashMap<String, Job>,
+}
+
+impl Monkeys {
+    /// Parse the job of each monkey, e.g. `root: pppw + sjmn` or `dbpl: 5`.
+    fn parse(data: &str) -> Result<Self, ParseError> {
+        let jobs = parse::lines(data, |line| {
+            let (name, job) = line.split_once(": ").ok_or(ParseError::Mismatch {
+                expected: "name: job".to_owned(),
+                found: line.to_owned(),
+            })?;
+
+            let job = match job.split(' ').collect::<Vec<_>>()[..] {
+                [a, op, b] => Job::Operation(a.to_owned(), op.try_into()?, b.to_owned()),
+                _ => Job::Number(parse::value(job)?),
+            };
+            Ok((name.to_owned(), job))
+        })?;
+
+        Ok(Self {
+            jobs: jobs.into_iter().collect(),
+        })
+    }
+
+    /// The expression of what a monkey yells, the `unknown` monkey yelling an unknown number.
+    ///
+    /// # Panics
+    /// If a monkey does not exist, or if an operation overflows or does not divide exactly.
+    fn expression(&self, name: &str, unknown: Option<&str>) -> Expr {
+        if Some(name) == unknown {
+            return Expr::Unknown;
+        }
+
+        match &self.jobs[name] {
+            Job::Number(n) => Expr::Number(*n),
+            Job::Operation(a, op, b) => {
+                match (self.expression(a, unknown), self.expression(b, unknown)) {
+                    (Expr::Number(a), Expr::Number(b)) => {
+                        Expr::Number(op.apply(a, b).unwrap_or_else(|err| {
+                            panic!("{a} {} {b} has no result: {err}", op.symbol())
+                        }))
+                    }
+                    (a, b) => Expr::Operation(Box::new(a), *op, Box::new(b)),
+                }
+            }
+        }
+    }
+
+    /// The equation of the `root` monkey checking that its two numbers are equal,
+    /// with the number of the `humn` monkey unknown.
+    fn equation(&self) -> Result<Equation, String> {
+        let Job::Operation(a, _, b) = &self.jobs[ROOT] else {
+            return Err(format!("{ROOT} does not compare two numbers"));
+        };
+
+        match (
+            self.expression(a, Some(HUMAN)),
+            self.expression(b, Some(HUMAN)),
+        ) {
+            (Expr::Number(value), unknown) | (unknown, Expr::Number(value)) => {
+                Ok(Equation { unknown, value })
+            }
+            (a, b) => Err(format!("{HUMAN} appears on both sides: {a} = {b}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Solution;
+
+    use super::{Day21, Monkeys, Op, OpError};
+
+    #[test]
+    fn q1() {
+        let day = Day21 {};
+
+        assert_eq!("152", day.q1(DATA1));
+    }
+
+    #[test]
+    fn q2() {
+        let day = Day21 {};
+
+        assert_eq!("301", day.q2(DATA1));
+    }
+
+    #[test]
+    fn equation() {
+        let monkeys = Monkeys::parse(DATA1).unwrap();
+        let equation = monkeys.equation().unwrap();
+        assert_eq!("((4 + (2 * (humn - 3))) / 4) = 150", equation.to_string());
+
+        // The division must be exact: 5 + 2 * (humn - 3) = 600 has no integer solution
+        let data = DATA1.replace("sllz: 4", "sllz: 5");
+        let monkeys = Monkeys::parse(&data).unwrap();
+        assert_eq!(None, monkeys.equation().unwrap().solve());
+
+        assert!(Monkeys::parse("root: a % b").is_err());
+    }
+
+    #[test]
+    fn operations() {
+        assert_eq!(Ok(-3), Op::Div.apply(9, -3));
+        assert_eq!(Err(OpError::Overflow), Op::Mul.apply(i64::MAX, 2));
+        assert_eq!(Err(OpError::Overflow), Op::Div.apply(i64::MIN, -1));
+        assert_eq!(Err(OpError::DivisionByZero), Op::Div.apply(1, 0));
+        assert_eq!(Err(OpError::InexactDivision), Op::Div.apply(7, 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "the division is not exact")]
+    fn inexact_root() {
+        let day = Day21 {};
+        let _ = day.q1(&DATA1.replace("lfqf: 4", "lfqf: 7"));
+    }
+
+    const DATA1: &str = "root: pppw + sjmn
+dbpl: 5
+cczh: sllz + lgvd
+zczc: 2
+ptdq: humn - dvpt
+dvpt: 3
+lfqf: 4
+humn: 5
+ljgn: 2
+sjmn: drzm * dbpl
+sllz: 4
+pppw: cczh / lfqf
+lgvd: ljgn * ptdq
+drzm: hmdt - zczc
+hmdt: 32";
+}
@@ -1,34 +1,438 @@
-use crate::Solution;
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    utils::{
+        geom::{Dir, Vec2, Vec3},
+        parse::ParseError,
+        CheckedYX, Grid,
+    },
+    Solution,
+};
 
 pub struct Day22;
 
 impl Solution for Day22 {
-    fn q1(&self, _data: &str) -> String {
-        todo!()
+    fn q1(&self, data: &str) -> String {
+        let (map, path) = parse_data(data).unwrap();
+        let (pos, dir) = map.walk(&path, |pos, dir| map.wrap_flat(pos, dir));
+        password(pos, dir).to_string()
+    }
+
+    fn q2(&self, data: &str) -> String {
+        let (map, path) = parse_data(data).unwrap();
+        let cube = Cube::fold(&map).unwrap();
+        let (pos, dir) = map.walk(&path, |pos, dir| cube.wrap(pos, dir));
+        password(pos, dir).to_string()
+    }
+}
+
+/// A position on the map, `y` pointing down
+type Position = Vec2<i32>;
+
+/// The password of the final position and facing.
+fn password(pos: Position, dir: Dir) -> i32 {
+    let facing = match dir {
+        Dir::Right => 0,
+        Dir::Down => 1,
+        Dir::Left => 2,
+        Dir::Up => 3,
+    };
+    1000 * (pos.y + 1) + 4 * (pos.x + 1) + facing
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tile {
+    /// Not part of the map
+    Void,
+    Open,
+    Wall,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Forward(u32),
+    TurnLeft,
+    TurnRight,
+}
+
+struct MonkeyMap(Grid<Tile>);
+
+impl MonkeyMap {
+    /// The tile at a position, which is void outside the map.
+    fn tile(&self, pos: Position) -> Tile {
+        CheckedYX::new_signed(&self.0, (pos.y as isize, pos.x as isize))
+            .map_or(Tile::Void, |pos| *self.0.get(pos))
+    }
+
+    /// Follow the path from the leftmost open tile of the top row, facing right,
+    /// and return the final position and direction.
+    ///
+    /// When stepping off the map, `wrap` gives the tile and direction to continue with.
+    fn walk(
+        &self,
+        path: &[Step],
+        wrap: impl Fn(Position, Dir) -> (Position, Dir),
+    ) -> (Position, Dir) {
+        let x = self.0.row(0).iter().position(|&t| t == Tile::Open).unwrap();
+        let mut pos = Position::new(x as i32, 0);
+        let mut dir = Dir::Right;
+
+        for &step in path {
+            match step {
+                Step::TurnLeft => dir = dir.turn_left(),
+                Step::TurnRight => dir = dir.turn_right(),
+                Step::Forward(n) => {
+                    for _ in 0..n {
+                        let (next, next_dir) = match pos + dir.delta() {
+                            next if self.tile(next) == Tile::Void => wrap(pos, dir),
+                            next => (next, dir),
+                        };
+                        if self.tile(next) == Tile::Wall {
+                            break;
+                        }
+                        (pos, dir) = (next, next_dir);
+                    }
+                }
+            }
+        }
+
+        (pos, dir)
     }
 
-    fn q2(&self, _data: &str) -> String {
-        todo!()
+    /// Wrap around to the other side of the map, as if it was flat.
+    fn wrap_flat(&self, pos: Position, dir: Dir) -> (Position, Dir) {
+        let back = dir.reverse().delta();
+        let mut pos = pos;
+        while self.tile(pos + back) != Tile::Void {
+            pos += back;
+        }
+        (pos, dir)
     }
 }
 
-// #[cfg(test)]
-// mod test {
-//     use crate::Solution;
+/// A face of the cube, as placed in the map and in 3D.
+#[derive(Debug, Clone, Copy)]
+struct Face {
+    /// The top left tile of the face on the map
+    origin: Position,
+    /// The 3D directions of the map right and down directions on this face
+    right: Vec3<i32>,
+    down: Vec3<i32>,
+    /// The direction the face is facing, out of the cube
+    normal: Vec3<i32>,
+}
+
+impl Face {
+    /// The 3D direction of a map direction on this face.
+    fn direction(&self, dir: Dir) -> Vec3<i32> {
+        match dir {
+            Dir::Up => -self.down,
+            Dir::Right => self.right,
+            Dir::Down => self.down,
+            Dir::Left => -self.right,
+        }
+    }
+}
+
+fn dot(a: Vec3<i32>, b: Vec3<i32>) -> i32 {
+    a.x * b.x + a.y * b.y + a.z * b.z
+}
+
+/// The map folded into a cube.
+///
+/// Positions are converted to 3D points on the surface of a cube centered on 0, in units
+/// of half tiles so that the tile centers have integer coordinates.
+struct Cube {
+    size: i32,
+    faces: Vec<Face>,
+}
+
+impl Cube {
+    /// Fold the net of the map into a cube, finding the 3D orientation of each face
+    /// from how it is attached to the others.
+    fn fold(map: &MonkeyMap) -> Result<Self, String> {
+        let tiles = map.0.data.iter().filter(|&&t| t != Tile::Void).count();
+        let size = (tiles as f64 / 6.).sqrt() as i32;
+        if 6 * size * size != tiles as i32 {
+            return Err(format!("{tiles} tiles cannot be folded into a cube"));
+        }
+
+        // The faces, by their origin
+        let mut faces = HashMap::new();
+
+        // Start from any face, then fold the attached faces around it
+        let x = map.0.row(0).iter().position(|&t| t != Tile::Void).unwrap();
+        let start = Face {
+            origin: Position::new(x as i32, 0),
+            right: Vec3::new(1, 0, 0),
+            down: Vec3::new(0, 1, 0),
+            normal: Vec3::new(0, 0, 1),
+        };
+        let mut queue = vec![start];
+        faces.insert(start.origin, start);
+
+        while let Some(face) = queue.pop() {
+            for dir in Dir::ALL {
+                let origin = face.origin + dir.delta() * size;
+                if map.tile(origin) == Tile::Void || faces.contains_key(&origin) {
+                    continue;
+                }
+
+                // Fold the face along the edge: it faces in the direction of the edge,
+                // and the direction of the edge points into the cube
+                let edge = face.direction(dir);
+                let (right, down) = match dir {
+                    Dir::Up => (face.right, face.normal),
+                    Dir::Right => (-face.normal, face.down),
+                    Dir::Down => (face.right, -face.normal),
+                    Dir::Left => (face.normal, face.down),
+                };
+                let next = Face {
+                    origin,
+                    right,
+                    down,
+                    normal: edge,
+                };
+                faces.insert(origin, next);
+                queue.push(next);
+            }
+        }
+
+        if faces.len() != 6 {
+            return Err(format!("the net has {} connected faces", faces.len()));
+        }
+        let normals = faces
+            .values()
+            .map(|face| face.normal)
+            .collect::<HashSet<_>>();
+        if normals.len() != 6 {
+            return Err("some faces overlap once folded".to_owned());
+        }
+        Ok(Self {
+            size,
+            faces: faces.into_values().collect(),
+        })
+    }
+
+    /// The face containing a position of the map.
+    fn face(&self, pos: Position) -> &Face {
+        self.faces
+            .iter()
+            .find(|face| {
+                (pos - face.origin)
+                    .in_bounds(Vec2::new(0, 0), Vec2::new(self.size - 1, self.size - 1))
+            })
+            .expect("The position is not on the cube")
+    }
 
-//     use super::Day22;
+    /// Wrap around to the adjacent face of the cube, when leaving a face through an edge.
+    fn wrap(&self, pos: Position, dir: Dir) -> (Position, Dir) {
+        let s = self.size;
+        let face = self.face(pos);
 
-//     #[test]
-//     fn q1() {
-//         let day = Day22 {};
+        // The 3D point of the tile center
+        let local = pos - face.origin;
+        let point = face.right * (2 * local.x - (s - 1))
+            + face.down * (2 * local.y - (s - 1))
+            + face.normal * s;
 
-//         assert_eq!("", day.q1(""));
-//     }
+        // The adjacent tile is on the face in the direction of the edge,
+        // half a tile further in that direction and half a tile closer to the center
+        let edge = face.direction(dir);
+        let next = self
+            .faces
+            .iter()
+            .find(|other| other.normal == edge)
+            .unwrap();
+        let point = point + edge - face.normal;
 
-//     #[test]
-//     fn q2() {
-//         let day = Day22 {};
+        let local = Position::new(
+            (dot(point, next.right) + s - 1) / 2,
+            (dot(point, next.down) + s - 1) / 2,
+        );
+        let next_dir = Dir::ALL
+            .into_iter()
+            .find(|&d| next.direction(d) == -face.normal)
+            .unwrap();
 
-//         assert_eq!("", day.q2(""));
-//     }
-// }
+        (next.origin + local, next_dir)
+    }
+}
+
+/// Parse the map, and the path after it.
+fn parse_data(data: &str) -> Result<(MonkeyMap, Vec<Step>), ParseError> {
+    let (map, path) = data.split_once("\n\n").ok_or(ParseError::Mismatch {
+        expected: "a map and a path separated by a blank line".to_owned(),
+        found: data.to_owned(),
+    })?;
+
+    // Rows are not as long as the map is wide, and only end with its last tile
+    let lines = map.lines().collect::<Vec<_>>();
+    let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+    let mut grid = Grid::new(lines.len(), width, Tile::Void);
+    for (y, line) in lines.iter().enumerate() {
+        for (x, c) in line.chars().enumerate() {
+            grid.row_mut(y)[x] = match c {
+                ' ' => Tile::Void,
+                '.' => Tile::Open,
+                '#' => Tile::Wall,
+                _ => {
+                    return Err(ParseError::InvalidValue {
+                        value: c.to_string(),
+                        ty: "Tile",
+                    }
+                    .at_line(y + 1))
+                }
+            };
+        }
+    }
+
+    let mut steps = vec![];
+    let mut n = None;
+    for c in path.trim().chars() {
+        if let Some(digit) = c.to_digit(10) {
+            n = Some(n.unwrap_or(0) * 10 + digit);
+            continue;
+        }
+
+        steps.extend(n.take().map(Step::Forward));
+        steps.push(match c {
+            'L' => Step::TurnLeft,
+            'R' => Step::TurnRight,
+            _ => {
+                return Err(ParseError::InvalidValue {
+                    value: c.to_string(),
+                    ty: "Step",
+                }
+                .at_line(lines.len() + 2))
+            }
+        });
+    }
+    steps.extend(n.map(Step::Forward));
+
+    Ok((MonkeyMap(grid), steps))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        utils::geom::{Dir, Vec2},
+        Solution,
+    };
+
+    use super::{parse_data, Cube, Day22, Step, Tile};
+
+    #[test]
+    fn q1() {
+        let day = Day22 {};
+
+        assert_eq!("6032", day.q1(DATA1));
+    }
+
+    #[test]
+    fn q2() {
+        let day = Day22 {};
+
+        assert_eq!("5031", day.q2(DATA1));
+    }
+
+    #[test]
+    fn parse() {
+        let (map, path) = parse_data(DATA1).unwrap();
+        assert_eq!((12, 16), (map.0.height, map.0.width));
+        assert_eq!(Tile::Void, map.tile(Vec2::new(15, 0)));
+        assert_eq!(Tile::Wall, map.tile(Vec2::new(11, 0)));
+        assert_eq!(
+            [Step::Forward(10), Step::TurnRight, Step::Forward(5)],
+            path[..3]
+        );
+        assert_eq!(Some(&Step::Forward(5)), path.last());
+    }
+
+    /// Check that the cube wrapping is consistent on a map without walls, from every edge tile.
+    fn check_cube(net: &str, size: usize) {
+        let map = net
+            .lines()
+            .flat_map(|row| {
+                let row = row
+                    .replace('#', &".".repeat(size))
+                    .replace(' ', &" ".repeat(size));
+                std::iter::repeat_n(row, size)
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let (map, _) = parse_data(&format!("{map}\n\n0")).unwrap();
+        let cube = Cube::fold(&map).unwrap();
+
+        for face in &cube.faces {
+            for i in 0..cube.size {
+                let s = cube.size - 1;
+                let edges = [
+                    (Vec2::new(i, 0), Dir::Up),
+                    (Vec2::new(s, i), Dir::Right),
+                    (Vec2::new(i, s), Dir::Down),
+                    (Vec2::new(0, i), Dir::Left),
+                ];
+
+                for (local, dir) in edges {
+                    let pos = face.origin + local;
+                    let (next, next_dir) = cube.wrap(pos, dir);
+                    assert_eq!(Tile::Open, map.tile(next));
+                    if map.tile(pos + dir.delta()) == Tile::Void {
+                        assert_eq!(Tile::Void, map.tile(next - next_dir.delta()));
+                    } else {
+                        // Faces attached in the map are attached the same way on the cube
+                        assert_eq!((pos + dir.delta(), dir), (next, next_dir));
+                    }
+
+                    // Going back leads to the same tile
+                    assert_eq!((pos, dir.reverse()), cube.wrap(next, next_dir.reverse()));
+
+                    // Going straight around the cube leads back to the start
+                    let (mut p, mut d) = (pos, dir);
+                    for _ in 0..4 * cube.size {
+                        (p, d) = match p + d.delta() {
+                            next if map.tile(next) == Tile::Void => cube.wrap(p, d),
+                            next => (next, d),
+                        };
+                    }
+                    assert_eq!((pos, dir), (p, d));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cube_nets() {
+        check_cube("  # \n### \n  ##", 4);
+        check_cube(" ##\n # \n## \n#  ", 50);
+        check_cube("##  \n ## \n  ##", 3);
+    }
+
+    #[test]
+    fn invalid_nets() {
+        let fold = |net: &str| {
+            let map = format!("{}\n\n0", net.replace('#', "."));
+            Cube::fold(&parse_data(&map).unwrap().0).map(|_| ())
+        };
+
+        assert!(fold("#  \n###\n#  \n#  ").is_err());
+        assert!(fold("##\n##\n##").is_err());
+        assert!(fold("## \n # \n  #").is_err());
+    }
+
+    const DATA1: &str = "        ...#
+        .#..
+        #...
+        ....
+...#.......#
+........#...
+..#....#....
+..........#.
+        ...#....
+        .....#..
+        .#......
+        ......#.
+
+10R5L5R10L4R5L5";
+}
@@ -1,34 +1,314 @@
-use crate::Solution;
+use std::fmt::Display;
+
+use crate::{
+    utils::{Grid, ParseGridError},
+    Solution,
+};
 
 pub struct Day23;
 
 impl Solution for Day23 {
-    fn q1(&self, _data: &str) -> String {
-        todo!()
+    fn q1(&self, data: &str) -> String {
+        const ROUNDS: usize = 10;
+
+        let mut elves = Elves::parse(data).unwrap();
+        for _ in 0..ROUNDS {
+            elves.round();
+        }
+        elves.empty_tiles().to_string()
+    }
+
+    fn q2(&self, data: &str) -> String {
+        let mut elves = Elves::parse(data).unwrap();
+        let mut rounds = 1;
+        while elves.round() {
+            rounds += 1;
+        }
+        rounds.to_string()
+    }
+}
+
+/// The directions the elves consider moving to, in their initial order: north, south, west, east.
+/// Each is given as `(dy, dx)`, with the two diagonals that must also be free.
+const DIRECTIONS: [[(isize, isize); 3]; 4] = [
+    [(-1, 0), (-1, -1), (-1, 1)],
+    [(1, 0), (1, -1), (1, 1)],
+    [(0, -1), (-1, -1), (1, -1)],
+    [(0, 1), (-1, 1), (1, 1)],
+];
+
+/// The elves, on a grid large enough to always keep a free border around them.
+///
+/// Elves are identified by their index in the grid data, so that their
+/// neighbours are at fixed offsets which only change when the grid grows.
+struct Elves {
+    grid: Grid<bool>,
+    elves: Vec<usize>,
+    /// The number of elves proposing to move to each tile, during a round
+    proposals: Vec<u8>,
+    rounds: usize,
+}
+
+impl Elves {
+    /// Parse the map of the elves (`#`) and the empty ground (`.`), with at least one elf.
+    fn parse(data: &str) -> Result<Self, ParseGridError> {
+        let grid = Grid::try_from_str_map(data.lines(), |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })?;
+        if !grid.data.contains(&true) {
+            return Err(ParseGridError::Empty);
+        }
+
+        let mut elves = Self {
+            elves: vec![],
+            proposals: vec![],
+            grid,
+            rounds: 0,
+        };
+        elves.grow();
+        Ok(elves)
+    }
+
+    /// Re-center the elves on a larger grid, leaving a border as wide as the elves' area.
+    fn grow(&mut self) {
+        let ((y0, x0), (y1, x1)) = self.bounds();
+        let (height, width) = (y1 - y0 + 1, x1 - x0 + 1);
+        let (margin_y, margin_x) = (height.max(8), width.max(8));
+
+        let mut grid = Grid::new(height + 2 * margin_y, width + 2 * margin_x, false);
+        for y in y0..=y1 {
+            let row = &self.grid.row(y)[x0..=x1];
+            grid.row_mut(y - y0 + margin_y)[margin_x..margin_x + width].copy_from_slice(row);
+        }
+
+        self.elves = (0..grid.data.len()).filter(|&i| grid.data[i]).collect();
+        self.proposals = vec![0; grid.data.len()];
+        self.grid = grid;
+    }
+
+    /// The smallest and largest `(y, x)` coordinates of the elves.
+    fn bounds(&self) -> ((usize, usize), (usize, usize)) {
+        let width = self.grid.width;
+        let positions = self.grid.data.iter().enumerate().filter(|(_, &elf)| elf);
+        positions.fold(
+            ((usize::MAX, usize::MAX), (0, 0)),
+            |((y0, x0), (y1, x1)), (i, _)| {
+                let (y, x) = (i / width, i % width);
+                ((y0.min(y), x0.min(x)), (y1.max(y), x1.max(x)))
+            },
+        )
+    }
+
+    /// The number of empty tiles in the smallest rectangle containing all the elves.
+    fn empty_tiles(&self) -> usize {
+        let ((y0, x0), (y1, x1)) = self.bounds();
+        (y1 - y0 + 1) * (x1 - x0 + 1) - self.elves.len()
+    }
+
+    /// Play a round, and return whether any elf moved.
+    ///
+    /// Each elf with a neighbour proposes to move towards the first free direction,
+    /// starting from a direction which rotates each round. Elves then move
+    /// to the tiles that only they proposed.
+    fn round(&mut self) -> bool {
+        let width = self.grid.width as isize;
+        let offset = |(dy, dx): (isize, isize)| dy * width + dx;
+        let neighbours = [-1, 0, 1]
+            .into_iter()
+            .flat_map(|dy| [-1, 0, 1].map(|dx| (dy, dx)))
+            .filter(|&d| d != (0, 0))
+            .map(offset)
+            .collect::<Vec<_>>();
+        let directions = DIRECTIONS.map(|tiles| tiles.map(offset));
+
+        let occupied = |i: usize, offset: isize| self.grid.data[i.wrapping_add_signed(offset)];
+        let moves = self
+            .elves
+            .iter()
+            .enumerate()
+            .filter(|&(_, &i)| neighbours.iter().any(|&offset| occupied(i, offset)))
+            .filter_map(|(elf, &i)| {
+                (0..4)
+                    .map(|d| directions[(self.rounds + d) % 4])
+                    .find(|tiles| tiles.iter().all(|&offset| !occupied(i, offset)))
+                    .map(|tiles| (elf, i.wrapping_add_signed(tiles[0])))
+            })
+            .collect::<Vec<_>>();
+
+        for &(_, to) in &moves {
+            self.proposals[to] += 1;
+        }
+
+        let mut moved = false;
+        let mut on_border = false;
+        for &(elf, to) in &moves {
+            if self.proposals[to] == 1 {
+                self.grid.data[self.elves[elf]] = false;
+                self.grid.data[to] = true;
+                self.elves[elf] = to;
+                moved = true;
+
+                let (y, x) = (to / self.grid.width, to % self.grid.width);
+                on_border |= y == 0 || x == 0;
+                on_border |= y == self.grid.height - 1 || x == self.grid.width - 1;
+            }
+        }
+        for &(_, to) in &moves {
+            self.proposals[to] = 0;
+        }
+
+        // The neighbours of the elves must stay on the grid
+        if on_border {
+            self.grow();
+        }
+        self.rounds += 1;
+        moved
     }
+}
 
-    fn q2(&self, _data: &str) -> String {
-        todo!()
+impl Display for Elves {
+    /// The smallest rectangle containing all the elves.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ((y0, x0), (y1, x1)) = self.bounds();
+        for y in y0..=y1 {
+            if y != y0 {
+                writeln!(f)?;
+            }
+            for &elf in &self.grid.row(y)[x0..=x1] {
+                write!(f, "{}", if elf { '#' } else { '.' })?;
+            }
+        }
+        Ok(())
     }
 }
 
-// #[cfg(test)]
-// mod test {
-//     use crate::Solution;
+#[cfg(test)]
+mod test {
+    use std::collections::{HashMap, HashSet};
 
-//     use super::Day23;
+    use crate::{
+        utils::test_rng::{Rng, SEED},
+        Solution,
+    };
 
-//     #[test]
-//     fn q1() {
-//         let day = Day23 {};
+    use super::{Day23, Elves, DIRECTIONS};
 
-//         assert_eq!("", day.q1(""));
-//     }
+    #[test]
+    fn q1() {
+        let day = Day23 {};
+
+        assert_eq!("110", day.q1(DATA1));
+    }
+
+    #[test]
+    fn q2() {
+        let day = Day23 {};
+
+        assert_eq!("20", day.q2(DATA1));
+    }
 
-//     #[test]
-//     fn q2() {
-//         let day = Day23 {};
+    #[test]
+    fn rounds() {
+        let mut elves = Elves::parse(".....\n..##.\n..#..\n.....\n..##.\n.....").unwrap();
+        assert!(elves.round());
+        assert_eq!("##\n..\n#.\n.#\n#.", elves.to_string());
+        assert!(elves.round());
+        assert!(elves.round());
+        assert_eq!(
+            "..#..\n....#\n#....\n....#\n.....\n..#..",
+            elves.to_string()
+        );
+        assert!(!elves.round());
+        assert_eq!(25, elves.empty_tiles());
 
-//         assert_eq!("", day.q2(""));
-//     }
-// }
+        assert!(Elves::parse("#.\n.x").is_err());
+        assert!(Elves::parse("..\n..").is_err());
+    }
+
+    #[test]
+    fn against_naive() {
+        /// Play the rounds on a set of positions, until no elf moves
+        fn naive(map: &str, rounds: usize) -> (usize, usize) {
+            let mut elves = HashSet::new();
+            for (y, line) in map.lines().enumerate() {
+                for (x, c) in line.chars().enumerate() {
+                    if c == '#' {
+                        elves.insert((y as i32, x as i32));
+                    }
+                }
+            }
+
+            let mut round = 0;
+            while round < rounds {
+                let mut proposals = HashMap::<_, Vec<_>>::new();
+                for &(y, x) in &elves {
+                    let free = |dy, dx| !elves.contains(&(y + dy, x + dx));
+                    let alone =
+                        (-1..=1).all(|dy| (-1..=1).all(|dx| (dy, dx) == (0, 0) || free(dy, dx)));
+                    if alone {
+                        continue;
+                    }
+                    let proposal = (0..4)
+                        .map(|d| DIRECTIONS[(round + d) % 4])
+                        .find(|tiles| tiles.iter().all(|&(dy, dx)| free(dy as i32, dx as i32)));
+                    if let Some([(dy, dx), ..]) = proposal {
+                        let to = (y + dy as i32, x + dx as i32);
+                        proposals.entry(to).or_default().push((y, x));
+                    }
+                }
+
+                round += 1;
+                let moves = proposals.into_iter().filter(|(_, from)| from.len() == 1);
+                let moves = moves.map(|(to, from)| (from[0], to)).collect::<Vec<_>>();
+                if moves.is_empty() {
+                    break;
+                }
+                for (from, to) in moves {
+                    elves.remove(&from);
+                    elves.insert(to);
+                }
+            }
+
+            let (ys, xs): (Vec<_>, Vec<_>) = elves.iter().copied().unzip();
+            let area = (ys.iter().max().unwrap() - ys.iter().min().unwrap() + 1)
+                * (xs.iter().max().unwrap() - xs.iter().min().unwrap() + 1);
+            (round, area as usize - elves.len())
+        }
+
+        let mut rng = Rng::new(SEED);
+        for _ in 0..30 {
+            let (height, width) = (rng.range(1..12) as usize, rng.range(1..12) as usize);
+            let density = rng.range(1..10);
+            let mut map = (0..height)
+                .map(|_| {
+                    (0..width)
+                        .map(|_| if rng.range(0..10) < density { '#' } else { '.' })
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            map.replace_range(0..1, "#");
+
+            let rounds = rng.range(1..200) as usize;
+            let mut elves = Elves::parse(&map).unwrap();
+            let mut round = 0;
+            while round < rounds {
+                round += 1;
+                if !elves.round() {
+                    break;
+                }
+            }
+            assert_eq!(naive(&map, rounds), (round, elves.empty_tiles()), "{map}");
+        }
+    }
+
+    const DATA1: &str = "....#..
+..###.#
+#...#.#
+.#...##
+#.###..
+##.#.##
+.#..#..";
+}
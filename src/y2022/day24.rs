@@ -1,34 +1,233 @@
-use crate::Solution;
+use crate::{
+    utils::{
+        geom::{Dir, Vec2},
+        math,
+        parse::ParseError,
+        search, Grid,
+    },
+    Solution,
+};
 
 pub struct Day24;
 
 impl Solution for Day24 {
-    fn q1(&self, _data: &str) -> String {
-        todo!()
+    fn q1(&self, data: &str) -> String {
+        let valley = Valley::parse(data).unwrap();
+        valley
+            .crossing(valley.start, valley.goal, 0)
+            .expect("The goal cannot be reached")
+            .to_string()
     }
 
-    fn q2(&self, _data: &str) -> String {
-        todo!()
+    fn q2(&self, data: &str) -> String {
+        let valley = Valley::parse(data).unwrap();
+        let trips = [
+            (valley.start, valley.goal),
+            (valley.goal, valley.start),
+            (valley.start, valley.goal),
+        ];
+        trips
+            .into_iter()
+            .try_fold(0, |time, (from, to)| {
+                valley
+                    .crossing(from, to, time)
+                    .map(|minutes| time + minutes)
+            })
+            .expect("The trips cannot be made")
+            .to_string()
     }
 }
 
-// #[cfg(test)]
-// mod test {
-//     use crate::Solution;
+/// A position in the valley, without its walls.
+/// The start is on the row above the valley, and the goal on the row below.
+type Position = Vec2<i32>;
 
-//     use super::Day24;
+/// The valley and its blizzards, whose positions repeat every `period` minutes.
+struct Valley {
+    width: usize,
+    height: usize,
+    start: Position,
+    goal: Position,
+    /// Whether the horizontal blizzards cover a tile at some time,
+    /// indexed by row, then time modulo the width, then column
+    horizontal: Vec<bool>,
+    /// Whether the vertical blizzards cover a tile at some time,
+    /// indexed by column, then time modulo the height, then row
+    vertical: Vec<bool>,
+    period: usize,
+}
+
+impl Valley {
+    /// Whether a position can be stood on at some time.
+    fn is_free(&self, pos: Position, time: usize) -> bool {
+        if pos == self.start || pos == self.goal {
+            return true;
+        }
+        if !pos.in_bounds(
+            Position::new(0, 0),
+            Position::new(self.width as i32 - 1, self.height as i32 - 1),
+        ) {
+            return false;
+        }
+
+        let (x, y) = (pos.x as usize, pos.y as usize);
+        let (w, h) = (self.width, self.height);
+        !self.horizontal[(y * w + time % w) * w + x] && !self.vertical[(x * h + time % h) * h + y]
+    }
+
+    /// The fewest minutes to go from a position to another, leaving at some time.
+    ///
+    /// Each minute, the expedition moves or waits, and must not end on a blizzard.
+    /// As blizzards repeat, states are positions at times modulo the period.
+    fn crossing(&self, from: Position, to: Position, time: usize) -> Option<usize> {
+        let neighbors = |&(pos, time): &(Position, usize)| {
+            let time = (time + 1) % self.period;
+            pos.neighbors4()
+                .chain([pos])
+                .filter(move |&next| self.is_free(next, time))
+                .map(move |next| (next, time))
+        };
+
+        search::bfs([(from, time % self.period)], neighbors, |&(pos, _)| {
+            pos == to
+        })
+        .goal_cost()
+    }
+
+    /// Parse the map of the valley, surrounded by walls except for one opening
+    /// at the top and one at the bottom.
+    /// Blizzards must not be able to go through the openings.
+    fn parse(data: &str) -> Result<Self, ParseError> {
+        let grid = Grid::try_from_str_map(data.lines(), |c| match c {
+            '#' | '.' => Some(None),
+            '^' => Some(Some(Dir::Up)),
+            '>' => Some(Some(Dir::Right)),
+            'v' => Some(Some(Dir::Down)),
+            '<' => Some(Some(Dir::Left)),
+            _ => None,
+        })
+        .map_err(|err| ParseError::Mismatch {
+            expected: "a map of the valley".to_owned(),
+            found: err.to_string(),
+        })?;
+
+        let opening = |y: usize| {
+            let row = data.lines().nth(y).unwrap_or_default();
+            match row.match_indices('.').collect::<Vec<_>>()[..] {
+                [(x, _)] if x > 0 && x + 1 < row.len() => Ok(x as i32 - 1),
+                _ => Err(ParseError::Mismatch {
+                    expected: "a wall with a single opening".to_owned(),
+                    found: row.to_owned(),
+                }),
+            }
+        };
+        let (width, height) = (grid.width.saturating_sub(2), grid.height.saturating_sub(2));
+        if height == 0 {
+            return Err(ParseError::Mismatch {
+                expected: "a valley between two walls".to_owned(),
+                found: data.to_owned(),
+            });
+        }
+        let start = Position::new(opening(0)?, -1);
+        let goal = Position::new(opening(grid.height - 1)?, height as i32);
+
+        let (w, h) = (width, height);
+        let mut horizontal = vec![false; h * w * w];
+        let mut vertical = vec![false; w * h * h];
+        for y in 0..h {
+            for (x, &blizzard) in grid.row(y + 1)[1..=w].iter().enumerate() {
+                let Some(dir) = blizzard else { continue };
+                let delta: Position = dir.delta();
+                if delta.y != 0 && (x as i32 == start.x || x as i32 == goal.x) {
+                    return Err(ParseError::Mismatch {
+                        expected: "no vertical blizzard in front of an opening".to_owned(),
+                        found: format!("{dir:?} at ({x}, {y})"),
+                    }
+                    .at_line(y + 2));
+                }
+
+                for time in 0..w {
+                    let x = (x as i32 + delta.x * time as i32).rem_euclid(w as i32) as usize;
+                    horizontal[(y * w + time) * w + x] |= delta.x != 0;
+                }
+                for time in 0..h {
+                    let y = (y as i32 + delta.y * time as i32).rem_euclid(h as i32) as usize;
+                    vertical[(x * h + time) * h + y] |= delta.y != 0;
+                }
+            }
+        }
+
+        Ok(Self {
+            width,
+            height,
+            start,
+            goal,
+            horizontal,
+            vertical,
+            period: math::lcm(width, height),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Solution;
 
-//     #[test]
-//     fn q1() {
-//         let day = Day24 {};
+    use super::{Day24, Position, Valley};
 
-//         assert_eq!("", day.q1(""));
-//     }
+    #[test]
+    fn q1() {
+        let day = Day24 {};
+
+        assert_eq!("18", day.q1(DATA1));
+    }
+
+    #[test]
+    fn q2() {
+        let day = Day24 {};
+
+        assert_eq!("54", day.q2(DATA1));
+    }
 
-//     #[test]
-//     fn q2() {
-//         let day = Day24 {};
+    #[test]
+    fn blizzards() {
+        let valley = Valley::parse(DATA2).unwrap();
+        assert_eq!(5, valley.period);
+        assert_eq!(Position::new(0, -1), valley.start);
+        assert_eq!(Position::new(4, 5), valley.goal);
 
-//         assert_eq!("", day.q2(""));
-//     }
-// }
+        // Both blizzards meet on the same tile after 3 minutes
+        let pos = Position::new(3, 1);
+        assert!(valley.is_free(pos, 0));
+        assert!(!valley.is_free(pos, 3));
+        assert!(!valley.is_free(pos, 8));
+        assert!(!valley.is_free(Position::new(3, 0), 5 + 2));
+        assert!(valley.is_free(valley.start, 3));
+        assert!(!valley.is_free(Position::new(0, -2), 0));
+
+        let valley = Valley::parse(DATA1).unwrap();
+        assert_eq!(12, valley.period);
+        assert_eq!(Some(18), valley.crossing(valley.start, valley.goal, 0));
+        assert_eq!(Some(23), valley.crossing(valley.goal, valley.start, 18));
+
+        assert!(Valley::parse("#.#\n#x#\n#.#").is_err());
+        assert!(Valley::parse("#..#\n#..#\n##.#").is_err());
+        assert!(Valley::parse("#.##\n#v.#\n##.#").is_err());
+        assert!(Valley::parse("#.#\n#.#").is_err());
+    }
+
+    const DATA1: &str = "#.######
+#>>.<^<#
+#.<..<<#
+#>v.><>#
+#<^v^^>#
+######.#";
+
+    const DATA2: &str = "#.#####
+#.....#
+#>....#
+#.....#
+#...v.#
+#.....#
+#####.#";
+}
@@ -2,7 +2,7 @@ use std::{fmt::Debug, str::FromStr};
 
 use itertools::Itertools;
 
-use crate::Solution;
+use crate::{utils::bitset::BitSet, Solution};
 
 pub struct Day3;
 
@@ -84,36 +84,29 @@ impl FromStr for Compartment {
 
 impl Item {
     fn parse(c: char) -> Item {
-        Item(if ('a'..='z').contains(&c) {
+        Item(if c.is_ascii_lowercase() {
             (c as u8) - b'a' + 1
         } else {
             (c as u8) - b'A' + 27
         })
     }
 
-    fn bit_mask(self) -> u64 {
-        1 << (self.0 - 1)
-    }
-
-    fn from_bit_mask(mask: u64) -> Item {
-        Item((mask.ilog2() + 1) as _)
+    /// The single item of a set of items.
+    fn from_set(items: BitSet) -> Item {
+        Item(items.iter().exactly_one().ok().expect("Not a single item") as _)
     }
 }
 
 impl Compartment {
-    fn item_mask(&self) -> u64 {
-        self.0
-            .iter()
-            .map(|item| item.bit_mask())
-            .fold(0, |acc, e| acc | e)
+    /// The set of the item priorities.
+    fn item_set(&self) -> BitSet {
+        self.0.iter().map(|item| item.0 as usize).collect()
     }
 }
 
 impl Rucksack {
     fn unique_item(&self) -> Item {
-        let mask1 = self.0.item_mask();
-        let mask2 = self.1.item_mask();
-        Item::from_bit_mask(mask1 & mask2)
+        Item::from_set(self.0.item_set() & self.1.item_set())
     }
 }
 
@@ -121,10 +114,7 @@ struct Rucksack2(Compartment, Compartment, Compartment);
 
 impl Rucksack2 {
     fn unique_item(&self) -> Item {
-        let mask1 = self.0.item_mask();
-        let mask2 = self.1.item_mask();
-        let mask3 = self.2.item_mask();
-        Item::from_bit_mask(mask1 & mask2 & mask3)
+        Item::from_set(self.0.item_set() & self.1.item_set() & self.2.item_set())
     }
 }
 
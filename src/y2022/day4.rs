@@ -1,6 +1,6 @@
 use std::{ops::RangeInclusive, str::FromStr};
 
-use crate::Solution;
+use crate::{utils::intervals::IntervalSet, Solution};
 
 pub struct Day4;
 
@@ -9,12 +9,9 @@ impl Solution for Day4 {
         let pairs = parse1(data);
         pairs
             .filter(|p| {
-                // Compute the intersection
-                p.intersection()
-                    // If the intersection bounds are both start & end bounds of either left or right
-                    // Then one of the assignment contains the other
-                    .map(|inter| [p.left.size(), p.right.size()].contains(&inter.size()))
-                    .unwrap_or(false)
+                // One of the assignments contains the other if nothing is left when removing it
+                let (left, right) = (p.left.sections(), p.right.sections());
+                left.difference(&right).is_empty() || right.difference(&left).is_empty()
             })
             .count()
             .to_string()
@@ -23,7 +20,15 @@ impl Solution for Day4 {
     fn q2(&self, data: &str) -> String {
         let pairs = parse1(data);
         // Even easier this time, we only have to check if there is an intersection
-        pairs.flat_map(|p| p.intersection()).count().to_string()
+        pairs
+            .filter(|p| {
+                !p.left
+                    .sections()
+                    .intersection(&p.right.sections())
+                    .is_empty()
+            })
+            .count()
+            .to_string()
     }
 }
 
@@ -64,21 +69,10 @@ impl FromStr for Assignment {
 }
 
 impl Assignment {
-    fn size(&self) -> u8 {
-        self.0.end() - self.0.start() + 1
-    }
-}
-
-impl Pair {
-    fn intersection(&self) -> Option<Assignment> {
-        let start = u8::max(*self.left.0.start(), *self.right.0.start());
-        let end = u8::min(*self.left.0.end(), *self.right.0.end());
-
-        if start > end {
-            None
-        } else {
-            Some(Assignment(start..=end))
-        }
+    /// The set of sections IDs of the assignment.
+    /// Its end is exclusive, so it is wider than the IDs to also hold the last one.
+    fn sections(&self) -> IntervalSet<u16> {
+        IntervalSet::from(u16::from(*self.0.start())..u16::from(*self.0.end()) + 1)
     }
 }
 
@@ -104,4 +98,10 @@ mod test {
     fn q2() {
         assert_eq!("4", Day4 {}.q2(DATA));
     }
+
+    #[test]
+    fn last_section() {
+        assert_eq!("1", Day4 {}.q1("0-255,255-255"));
+        assert_eq!("0", Day4 {}.q2("0-254,255-255"));
+    }
 }
@@ -1,8 +1,10 @@
-use std::cmp::Ordering;
 use std::collections::HashSet;
 use std::num::NonZeroU8;
 
-use crate::Solution;
+use crate::{
+    utils::geom::{Dir, Vec2},
+    Solution,
+};
 
 pub struct Day9;
 
@@ -12,7 +14,7 @@ impl Solution for Day9 {
 
         let mut visited = HashSet::new();
 
-        let mut head = Position { y: 0, x: 0 };
+        let mut head = Position::new(0, 0);
         let mut tail = head;
         visited.insert(tail);
         for mvmt in movements {
@@ -27,8 +29,8 @@ impl Solution for Day9 {
 
         let mut visited = HashSet::new();
 
-        let mut rope = [Position { y: 0, x: 0 }; 10];
-        visited.insert(Position { y: 0, x: 0 });
+        let mut rope = [Position::new(0, 0); 10];
+        visited.insert(Position::new(0, 0));
         for mvmt in movements {
             advance_rope(&mut rope, mvmt, &mut visited);
         }
@@ -39,33 +41,17 @@ impl Solution for Day9 {
 fn parse1(data: &str) -> impl Iterator<Item = Movement> + '_ {
     data.lines().map(str::trim).map(|line| {
         let (dir, dist) = line.split_once(' ').unwrap();
-        let dir = match dir.chars().next().unwrap() {
-            'U' => Direction::Up,
-            'D' => Direction::Down,
-            'L' => Direction::Left,
-            'R' => Direction::Right,
-            _ => unreachable!("Invalid position"),
-        };
+        let dir = Dir::try_from(dir.chars().next().unwrap()).expect("Invalid direction");
         let dist = dist.parse().unwrap();
         Movement { dir, dist }
     })
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Position {
-    y: i16,
-    x: i16,
-}
+type Position = Vec2<i16>;
 
 #[must_use]
-fn advance_head(mut head: Position, mvmt: Movement) -> Position {
-    match mvmt.dir {
-        Direction::Up => head.y -= mvmt.dist.get() as i16,
-        Direction::Down => head.y += mvmt.dist.get() as i16,
-        Direction::Left => head.x -= mvmt.dist.get() as i16,
-        Direction::Right => head.x += mvmt.dist.get() as i16,
-    }
-    head
+fn advance_head(head: Position, mvmt: Movement) -> Position {
+    head + mvmt.dir.delta() * mvmt.dist.get() as i16
 }
 
 #[must_use]
@@ -75,26 +61,11 @@ fn advance_tail(
     mut visited: Option<&mut HashSet<Position>>,
 ) -> Position {
     loop {
-        let dx = match head.x.cmp(&tail.x) {
-            Ordering::Less => -1,
-            Ordering::Equal => 0,
-            Ordering::Greater => 1,
-        };
-        let dy = match head.y.cmp(&tail.y) {
-            Ordering::Less => -1,
-            Ordering::Equal => 0,
-            Ordering::Greater => 1,
-        };
-
-        let new_position = Position {
-            y: tail.y + dy,
-            x: tail.x + dx,
-        };
-        if new_position == head {
+        if tail.chebyshev(head) <= 1 {
             // Tail is already next to the head, stop here
             return tail;
         }
-        tail = new_position;
+        tail = tail + (head - tail).signum();
         if let Some(ref mut visited) = visited {
             visited.insert(tail);
         }
@@ -105,7 +76,7 @@ fn advance_rope(rope: &mut [Position; 10], mvmt: Movement, visited: &mut HashSet
     let (head, rest) = rope.split_first_mut().unwrap();
     let (tail, middles) = rest.split_last_mut().unwrap();
 
-    const ONE: NonZeroU8 = unsafe { NonZeroU8::new_unchecked(1) };
+    const ONE: NonZeroU8 = NonZeroU8::new(1).unwrap();
     for _i in 0..mvmt.dist.get() {
         *head = advance_head(
             *head,
@@ -123,17 +94,9 @@ fn advance_rope(rope: &mut [Position; 10], mvmt: Movement, visited: &mut HashSet
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-enum Direction {
-    Right,
-    Up,
-    Left,
-    Down,
-}
-
 #[derive(Debug, Clone, Copy)]
 struct Movement {
-    dir: Direction,
+    dir: Dir,
     dist: NonZeroU8,
 }
 